@@ -9,6 +9,9 @@ fi
 
 echo ">> Deploying contract"
 
+# Only for the first deployment, later code is staged with `stage_code` and deployed
+# by executing an UpgradeSelf proposal of the trusted members
+
 # https://docs.near.org/tools/near-cli#near-dev-deploy
 # rm -rf neardev &&
 near contract deploy fusionfund.testnet use-file ./target/wasm32-unknown-unknown/release/fund.wasm without-init-call network-config testnet sign-with-keychain send
@@ -0,0 +1,55 @@
+use crate::*;
+
+use near_sdk::{env, AccountId};
+
+pub const MAX_ALLOWLIST_LEN: usize = 100;
+
+// Hex encoded sha256 of an access code
+pub(crate) fn hash_access_code(code: &str) -> String {
+    env::sha256(code.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Campaign {
+    // Private campaigns take pledges from allowlisted accounts or whoever knows the code
+    pub(crate) fn require_access(&self, contributor: &AccountId, access_code: Option<&str>) {
+        if !self.private {
+            return;
+        }
+        let allowlisted = self.allowlist.contains(contributor);
+        let knows_code = match (&self.access_code_hash, access_code) {
+            (Some(hash), Some(code)) => &hash_access_code(code) == hash,
+            _ => false,
+        };
+        require!(allowlisted || knows_code, "Campaign is private");
+    }
+}
+
+#[near]
+impl Contract {
+
+    // `code_hash` is the hex sha256 of the access code, computed off-chain so the code
+    // itself never appears in a transaction before backers use it. The code is not bound
+    // to anyone: the first backer to use it publishes it in their `contribute` arguments,
+    // after which anyone reading the chain can pledge. Treat it as a shareable invite and
+    // put members who must be the only ones let in on the allowlist. The public
+    // `campaign_code` of a private campaign is cleared and dropped from the code index.
+    pub fn set_campaign_access(&mut self, campaign_id: u64, private: bool, code_hash: Option<String>, allowlist: Vec<AccountId>) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can change campaign access");
+        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Crowdfunding has ended");
+        require!(allowlist.len() <= MAX_ALLOWLIST_LEN, "Allowlist is too long");
+        if let Some(hash) = &code_hash {
+            require!(hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()), "Code hash must be a hex sha256");
+        }
+
+        campaign.private = private;
+        campaign.access_code_hash = code_hash.map(|hash| hash.to_ascii_lowercase());
+        campaign.allowlist = allowlist;
+        if private && !campaign.campaign_code.is_empty() {
+            let code = std::mem::take(&mut campaign.campaign_code);
+            self.campaign_codes.remove(&code);
+        }
+    }
+
+}
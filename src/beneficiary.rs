@@ -0,0 +1,53 @@
+use crate::*;
+
+use near_sdk::{env, AccountId};
+
+impl Campaign {
+    // Payouts wait until a designated beneficiary, or every team member, has accepted
+    // the campaign
+    pub(crate) fn require_payable(&self) {
+        require!(
+            self.beneficiary.is_none() || self.beneficiary_accepted,
+            "Beneficiary has not accepted the campaign"
+        );
+        require!(self.team_accepted(), "Team members have not all accepted their roles");
+    }
+
+    pub(crate) fn is_payable(&self) -> bool {
+        (self.beneficiary.is_none() || self.beneficiary_accepted) && self.team_accepted()
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn accept_beneficiary(&mut self, campaign_id: u64) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(
+            campaign.beneficiary.as_ref() == Some(&env::predecessor_account_id()),
+            "Only the beneficiary can accept the campaign"
+        );
+        require!(!campaign.beneficiary_accepted, "Beneficiary already accepted");
+
+        campaign.beneficiary_accepted = true;
+    }
+
+    // Replaces a beneficiary that hasn't accepted, or drops it so payouts go to the
+    // creator. Backers pledge knowing who gets paid, so it is fixed once they contribute;
+    // the creator can cancel the campaign if the beneficiary never accepts.
+    pub fn set_beneficiary(&mut self, campaign_id: u64, beneficiary: Option<AccountId>) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can set the beneficiary");
+        require!(!campaign.has_contributions(), "Cannot change the beneficiary after contributions");
+        require!(!campaign.beneficiary_accepted, "Beneficiary already accepted");
+
+        let beneficiary = beneficiary.filter(|b| b != &campaign.creator);
+        if beneficiary.is_some() {
+            require!(campaign.team.is_empty(), "Campaigns with a team pay out to it");
+        }
+        campaign.beneficiary = beneficiary;
+    }
+
+}
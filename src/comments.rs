@@ -0,0 +1,128 @@
+use crate::*;
+
+use near_sdk::borsh;
+use near_sdk::{env, AccountId};
+
+pub const MAX_COMMENT_LENGTH: usize = 1_000;
+pub const MAX_PINNED_COMMENTS: usize = 5;
+const DEFAULT_COMMENTS_PAGE: u32 = 20;
+// Storage key and vector slot written next to each comment
+const COMMENT_STORAGE_OVERHEAD: u64 = 64;
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Comment {
+    pub author: AccountId,
+    pub text: String,
+    pub reply_to: Option<u32>,
+    pub backer: bool, // the author had contributed when posting
+    pub timestamp: U64,
+    pub hidden: bool, // text removed by a trusted member
+}
+
+impl Contract {
+    fn comments_of(&mut self, campaign_id: u64) -> &mut Vector<Comment> {
+        self.campaign_comments
+            .entry(campaign_id)
+            .or_insert_with(|| Vector::new(Prefix::CampaignCommentsOf(campaign_id)))
+    }
+
+    pub(crate) fn comment_exists(&self, campaign_id: u64, index: u32) -> bool {
+        self.campaign_comments.get(&campaign_id).is_some_and(|comments| index < comments.len())
+    }
+
+    // Moderation passes through a HideComment proposal, the deposit of a hidden comment
+    // is not returned
+    pub(crate) fn internal_hide_comment(&mut self, campaign_id: u64, index: u32) {
+        let comment = self
+            .campaign_comments
+            .get_mut(&campaign_id)
+            .and_then(|comments| comments.get_mut(index))
+            .expect("Comment does not exist");
+        comment.hidden = true;
+        comment.text = String::new();
+
+        if let Some(campaign) = self.campaigns.get_mut(&campaign_id) {
+            campaign.pinned_comments.retain(|i| *i != index);
+        }
+    }
+}
+
+#[near]
+impl Contract {
+
+    // The attached deposit pays for the comment's storage, the rest is sent back
+    #[payable]
+    pub fn post_comment(&mut self, campaign_id: u64, text: String, reply_to: Option<u32>) -> u32 {
+        let author = env::predecessor_account_id();
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.require_not_banned(&author);
+        require!(self.users.contains_key(&author), "User profile not found");
+        require!(!text.is_empty() && text.len() <= MAX_COMMENT_LENGTH, "Invalid comment length");
+
+        let comment = Comment {
+            backer: self.contribution_of(campaign_id, &author) > 0,
+            author: author.clone(),
+            text,
+            reply_to,
+            timestamp: U64(env::block_timestamp()),
+            hidden: false,
+        };
+
+        let bytes = borsh::to_vec(&comment).unwrap().len() as u64 + COMMENT_STORAGE_OVERHEAD;
+        storage::charge_storage_deposit(author, bytes, "comment");
+
+        let comments = self.comments_of(campaign_id);
+        if let Some(parent) = reply_to {
+            require!(parent < comments.len(), "Comment to reply to does not exist");
+        }
+        comments.push(comment);
+        comments.len() - 1
+    }
+
+    // Creators highlight answers at the top of the thread
+    pub fn pin_comment(&mut self, campaign_id: u64, index: u32, pinned: bool) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can pin comments");
+
+        campaign.pinned_comments.retain(|i| *i != index);
+        if pinned {
+            let comment = self
+                .campaign_comments
+                .get(&campaign_id)
+                .and_then(|comments| comments.get(index))
+                .expect("Comment does not exist");
+            require!(!comment.hidden, "Hidden comments cannot be pinned");
+            require!(campaign.pinned_comments.len() < MAX_PINNED_COMMENTS, "Too many pinned comments");
+            campaign.pinned_comments.push(index);
+        }
+    }
+
+    // Newest first, `from_index` counts back from the latest comment
+    pub fn get_campaign_comments(&self, campaign_id: u64, from_index: Option<u32>, limit: Option<u32>) -> Vec<(u32, &Comment)> {
+        let Some(comments) = self.campaign_comments.get(&campaign_id) else {
+            return Vec::new();
+        };
+
+        (0..comments.len())
+            .rev()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_COMMENTS_PAGE) as usize)
+            .filter_map(|index| comments.get(index).map(|comment| (index, comment)))
+            .collect()
+    }
+
+    pub fn get_pinned_comments(&self, campaign_id: u64) -> Vec<(u32, &Comment)> {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let Some(comments) = self.campaign_comments.get(&campaign_id) else {
+            return Vec::new();
+        };
+
+        campaign
+            .pinned_comments
+            .iter()
+            .filter_map(|index| comments.get(*index).map(|comment| (*index, comment)))
+            .collect()
+    }
+
+}
@@ -1,27 +1,218 @@
 use crate::*;
 
+use near_sdk::json_types::{Base58CryptoHash, Base64VecU8};
 use near_sdk::{env, AccountId};
 
+pub const DEFAULT_PLATFORM_FEE_BPS: u16 = 250;
+pub const MAX_PLATFORM_FEE_BPS: u16 = 1_000;
+// Proposals pass with more than half of the trusted members by default
+pub const DEFAULT_APPROVAL_THRESHOLD_BPS: u16 = 5_000;
+pub const MAX_FUNCTION_CALL_GAS: Gas = Gas::from_tgas(250);
 
+// Rules the DAO can change through ChangePolicy proposals
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Policy {
+    pub approval_threshold_bps: u16, // share of trusted members a proposal must exceed
+    pub kyc_required_above: NearToken, // campaign goals above this need a verified creator
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            approval_threshold_bps: DEFAULT_APPROVAL_THRESHOLD_BPS,
+            kyc_required_above: KYC_REQUIRED_ABOVE,
+        }
+    }
+}
+
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub enum PolicyParameter {
+    ApprovalThreshold { bps: u16 },
+    KycRequiredAbove { amount: NearToken },
+}
+
+// What a proposal does once it passes
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub enum ProposalKind {
+    Text,
+    SetPlatformFee { fee_bps: u16 },
+    // Moves `matching_pool` out of the treasury into a new quadratic funding round
+    CreateFundingRound { name: String, matching_pool: NearToken, ends_at: U64 },
+    // Deploys code staged with `stage_code` to this contract and runs `migrate`
+    UpgradeSelf { code_hash: Base58CryptoHash },
+    TreasuryTransfer { receiver_id: AccountId, amount: NearToken },
+    // Pays out fees collected in a campaign token
+    TokenTreasuryTransfer { token_id: AccountId, receiver_id: AccountId, amount: U128 },
+    // Lists or delists a NEP-141 token campaigns may raise in
+    SetTokenAccepted { token_id: AccountId, accepted: bool },
+    AddTrustedMember { member_id: AccountId },
+    RemoveTrustedMember { member_id: AccountId },
+    ChangePolicy { parameter: PolicyParameter },
+    BanUser { account_id: AccountId },
+    UnbanUser { account_id: AccountId },
+    // Stops contributions and payouts while frozen, refunds stay open
+    FreezeCampaign { campaign_id: u64, frozen: bool },
+    // Removes the text of a comment and unpins it
+    HideComment { campaign_id: u64, index: u32 },
+    // Calls any contract, the deposit comes out of the treasury
+    FunctionCall { receiver_id: AccountId, method_name: String, args: Base64VecU8, deposit: NearToken, gas: Gas },
+}
+
+impl Contract {
+    fn validate_proposal_kind(&self, kind: &ProposalKind) {
+        match kind {
+            ProposalKind::Text => {}
+            ProposalKind::SetPlatformFee { fee_bps } => {
+                require!(*fee_bps <= MAX_PLATFORM_FEE_BPS, "Platform fee is too high");
+            }
+            ProposalKind::CreateFundingRound { matching_pool, .. } => {
+                require!(!matching_pool.is_zero(), "Matching pool must be positive");
+            }
+            ProposalKind::UpgradeSelf { code_hash } => Self::require_staged_code(code_hash),
+            ProposalKind::TreasuryTransfer { amount, .. } => {
+                require!(!amount.is_zero(), "Transfer amount must be positive");
+            }
+            ProposalKind::TokenTreasuryTransfer { amount, .. } => {
+                require!(amount.0 > 0, "Transfer amount must be positive");
+            }
+            ProposalKind::SetTokenAccepted { .. } => {}
+            ProposalKind::AddTrustedMember { member_id } => {
+                require!(!self.is_a_trusted_member(member_id), "Already a trusted member");
+            }
+            ProposalKind::RemoveTrustedMember { member_id } => {
+                require!(self.is_a_trusted_member(member_id), "Not a trusted member");
+            }
+            ProposalKind::ChangePolicy { parameter: PolicyParameter::ApprovalThreshold { bps } } => {
+                require!(
+                    (DEFAULT_APPROVAL_THRESHOLD_BPS..BPS_DENOMINATOR as u16).contains(bps),
+                    "Approval threshold must be at least half and below all members"
+                );
+            }
+            ProposalKind::ChangePolicy { parameter: PolicyParameter::KycRequiredAbove { .. } } => {}
+            ProposalKind::BanUser { .. } | ProposalKind::UnbanUser { .. } => {}
+            ProposalKind::FreezeCampaign { campaign_id, .. } => {
+                require!(self.campaigns.contains_key(campaign_id), "Campaign does not exist");
+            }
+            ProposalKind::HideComment { campaign_id, index } => {
+                require!(self.comment_exists(*campaign_id, *index), "Comment does not exist");
+            }
+            ProposalKind::FunctionCall { receiver_id, method_name, gas, .. } => {
+                // The contract's own methods are reached through their proposal kinds, a
+                // call from itself would pass every #[private] and DAO-only check
+                require!(receiver_id != &env::current_account_id(), "Proposals cannot call the contract itself");
+                require!(!method_name.is_empty(), "Method name is required");
+                require!(*gas <= MAX_FUNCTION_CALL_GAS, "Too much gas for the call");
+            }
+        }
+    }
+
+    // Takes `amount` out of the treasury for a proposal to spend
+    fn spend_treasury(&mut self, amount: NearToken) {
+        self.treasury = self.treasury.checked_sub(amount).expect("Treasury cannot cover the proposal");
+    }
+
+    fn execute_proposal_kind(&mut self, kind: ProposalKind) {
+        match kind {
+            ProposalKind::Text => {}
+            ProposalKind::SetPlatformFee { fee_bps } => self.platform_fee_bps = fee_bps,
+            ProposalKind::CreateFundingRound { name, matching_pool, ends_at } => {
+                self.internal_create_funding_round(name, matching_pool, ends_at)
+            }
+            ProposalKind::UpgradeSelf { code_hash } => self.internal_upgrade_self(code_hash),
+            ProposalKind::TreasuryTransfer { receiver_id, amount } => {
+                self.spend_treasury(amount);
+                Promise::new(receiver_id).transfer(amount).detach();
+            }
+            ProposalKind::TokenTreasuryTransfer { token_id, receiver_id, amount } => {
+                let balance = self.token_treasury.get_mut(&token_id).expect("Treasury holds none of this token");
+                balance.0 = balance.0.checked_sub(amount.0).expect("Treasury cannot cover the proposal");
+                Self::transfer_funds(&Some(token_id), receiver_id, amount.0).detach();
+            }
+            ProposalKind::SetTokenAccepted { token_id, accepted } => {
+                if accepted {
+                    self.accepted_tokens.insert(token_id);
+                } else {
+                    self.accepted_tokens.remove(&token_id);
+                }
+            }
+            ProposalKind::AddTrustedMember { member_id } => {
+                require!(!self.is_a_trusted_member(&member_id), "Already a trusted member");
+                self.trusted_members.push(member_id);
+            }
+            ProposalKind::RemoveTrustedMember { member_id } => {
+                let index = self
+                    .trusted_members
+                    .iter()
+                    .position(|m| *m == member_id)
+                    .expect("Not a trusted member");
+                require!(self.trusted_members.len() > 1, "Cannot remove the last trusted member");
+                self.trusted_members.swap_remove(index as u32);
+            }
+            ProposalKind::ChangePolicy { parameter } => match parameter {
+                PolicyParameter::ApprovalThreshold { bps } => self.policy.approval_threshold_bps = bps,
+                PolicyParameter::KycRequiredAbove { amount } => self.policy.kyc_required_above = amount,
+            },
+            ProposalKind::BanUser { account_id } => self.internal_ban_user(account_id),
+            ProposalKind::UnbanUser { account_id } => {
+                self.banned_users.remove(&account_id);
+            }
+            ProposalKind::FreezeCampaign { campaign_id, frozen } => {
+                if frozen {
+                    self.frozen_campaigns.insert(campaign_id);
+                } else {
+                    self.frozen_campaigns.remove(&campaign_id);
+                }
+            }
+            ProposalKind::HideComment { campaign_id, index } => self.internal_hide_comment(campaign_id, index),
+            ProposalKind::FunctionCall { receiver_id, method_name, args, deposit, gas } => {
+                self.spend_treasury(deposit);
+                Promise::new(receiver_id).function_call(method_name, args.0, deposit, gas).detach();
+            }
+        }
+    }
+
+    // Counts the votes of current members only, so removed members stop counting
+    fn count_votes(&self, proposal_id: u64) -> (u64, u64) {
+        let (mut votes_for, mut votes_against) = (0, 0);
+        for member in self.trusted_members.iter() {
+            match self.proposal_votes.get(&(proposal_id, member.clone())) {
+                Some(true) => votes_for += 1,
+                Some(false) => votes_against += 1,
+                None => {}
+            }
+        }
+        (votes_for, votes_against)
+    }
+
+    pub(crate) fn require_not_frozen(&self, campaign_id: u64) {
+        require!(!self.frozen_campaigns.contains(&campaign_id), "Campaign is frozen by the DAO");
+    }
+}
 
 // Struct to store a proposal
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct Proposal {
-    id: u64,
-    proposer: AccountId,
-    description: String,
-    votes_for: u64,
-    votes_against: u64,
-    executed: bool,
+    pub id: u64,
+    pub proposer: AccountId,
+    pub description: String,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub executed: bool,
+    pub kind: ProposalKind,
 }
 
 #[near]
 impl Contract {
 
-    
+    // Seats the first trusted member, everyone after them joins through an
+    // AddTrustedMember proposal
     pub fn add_trusted_member(&mut self, member_id: AccountId) {
-        assert!(env::signer_account_id() == env::current_account_id(), "Only the DAO can add members.");
+        assert!(env::predecessor_account_id() == env::current_account_id(), "Only the DAO can add members.");
+        require!(self.trusted_members.is_empty(), "Trusted members join through AddTrustedMember proposals");
         self.trusted_members.push(member_id);
     }
 
@@ -30,16 +221,21 @@ impl Contract {
         self.trusted_members.iter().any(|m| m == member_id)
     }
 
-    pub fn create_proposal(&mut self, description: String) {
-        assert!(self.is_a_trusted_member(&env::signer_account_id()), "Only trusted members can create proposals.");
+    pub fn create_proposal(&mut self, description: String, kind: Option<ProposalKind>) {
+        let proposer = env::predecessor_account_id();
+        assert!(self.is_a_trusted_member(&proposer), "Only trusted members can create proposals.");
+
+        let kind = kind.unwrap_or(ProposalKind::Text);
+        self.validate_proposal_kind(&kind);
 
         let proposal = Proposal {
             id: self.proposal_count,
-            proposer: env::signer_account_id(),
+            proposer,
             description,
             votes_for: 0,
             votes_against: 0,
             executed: false,
+            kind,
         };
 
         self.proposals.insert(self.proposal_count, proposal);
@@ -48,12 +244,14 @@ impl Contract {
 
     pub fn vote(&mut self, proposal_id: u64, support: bool) {
 
-        assert!(self.is_a_trusted_member(&env::signer_account_id()), "Only trusted members can vote.");
+        let member = env::predecessor_account_id();
+        assert!(self.is_a_trusted_member(&member), "Only trusted members can vote.");
 
         let proposal = self.proposals.get_mut(&proposal_id).expect("Proposal not found.");
 
         
         assert!(!proposal.executed, "Proposal has already been executed.");
+        assert!(self.proposal_votes.insert((proposal_id, member), support).is_none(), "Already voted on this proposal.");
 
         if support {
             proposal.votes_for += 1;
@@ -65,25 +263,52 @@ impl Contract {
     }
 
     pub fn execute_proposal(&mut self, proposal_id: u64) {
+        let (votes_for, votes_against) = self.count_votes(proposal_id);
         let proposal = self.proposals.get_mut(&proposal_id).expect("Proposal not found.");
         
-        let total_votes = proposal.votes_for + proposal.votes_against;
-        let required_votes = self.trusted_members.len() / 2; // Majority rule
+        // Votes must exceed the policy's share of trusted members
+        let required_votes = self.trusted_members.len() as u64 * self.policy.approval_threshold_bps as u64;
 
-        assert!(proposal.votes_for > required_votes as u64, "Proposal did not pass.");
+        assert!(votes_for * BPS_DENOMINATOR as u64 > required_votes, "Proposal did not pass.");
         assert!(!proposal.executed, "Proposal already executed.");
 
+        proposal.votes_for = votes_for;
+        proposal.votes_against = votes_against;
         proposal.executed = true;
         // self.proposals.insert(&proposal_id, &proposal);
 
-        // Execution 
-        // .........
+        let kind = proposal.kind.clone();
+        self.execute_proposal_kind(kind);
     }
 
     #[payable]
     pub fn contribute_to_treasury(&mut self) {
         let amount = env::attached_deposit();
-        self.treasury += amount.as_near() as u64;
+        self.treasury = self.treasury.checked_add(amount).expect("Treasury overflow");
+    }
+
+    pub fn get_treasury_balance(&self) -> NearToken {
+        self.treasury
+    }
+
+    pub fn get_token_treasury_balance(&self, token_id: AccountId) -> U128 {
+        self.token_treasury.get(&token_id).copied().unwrap_or(U128(0))
+    }
+
+    pub fn is_token_accepted(&self, token_id: AccountId) -> bool {
+        self.accepted_tokens.contains(&token_id)
+    }
+
+    pub fn get_platform_fee_bps(&self) -> u16 {
+        self.platform_fee_bps
+    }
+
+    pub fn get_policy(&self) -> Policy {
+        self.policy.clone()
+    }
+
+    pub fn is_campaign_frozen(&self, campaign_id: u64) -> bool {
+        self.frozen_campaigns.contains(&campaign_id)
     }
 
     pub fn get_proposal(&self, proposal_id: u64) -> Proposal {
@@ -0,0 +1,102 @@
+use crate::*;
+
+use near_sdk::{env, is_promise_success, log};
+
+pub const MAX_CAMPAIGN_EXTENSIONS: u8 = 2;
+// Time backers get to pull their pledge after the deadline moves, 3 days in nanoseconds
+pub const EXTENSION_OPT_OUT_PERIOD: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+
+impl Campaign {
+    pub(crate) fn in_opt_out_window(&self) -> bool {
+        self.opt_out_ends_at.is_some_and(|ends_at| env::block_timestamp() <= ends_at.0)
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn extend_campaign(&mut self, campaign_id: u64, additional_time: U64) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        let now = env::block_timestamp();
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can extend the campaign");
+        require!(now < campaign.crowdfunding_end_time.into(), "Campaign has already ended");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+        require!(campaign.extensions < MAX_CAMPAIGN_EXTENSIONS, "Campaign cannot be extended again");
+        require!(additional_time.0 > 0, "Extension must be positive");
+
+        let new_end_time = campaign.crowdfunding_end_time.0.checked_add(additional_time.0).expect("End time overflow");
+        require!(new_end_time > now + EXTENSION_OPT_OUT_PERIOD, "Campaign must stay open through the opt-out window");
+        if let Some(first) = campaign.milestones.first() {
+            require!(new_end_time < first.due_date.0, "Extension would pass the first milestone due date");
+        }
+
+        campaign.crowdfunding_end_time = U64(new_end_time);
+        campaign.extensions += 1;
+        campaign.opt_out_ends_at = Some(U64(now + EXTENSION_OPT_OUT_PERIOD));
+
+        log!(
+            "Campaign {} extended to {}, backers may withdraw their pledge until {}",
+            campaign_id,
+            new_end_time,
+            now + EXTENSION_OPT_OUT_PERIOD
+        );
+    }
+
+    // Backers who don't accept an extension take their whole pledge back
+    pub fn withdraw_pledge(&mut self, campaign_id: u64) -> Promise {
+        let contributor = env::predecessor_account_id();
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(campaign.in_opt_out_window(), "Campaign is not in an opt-out window");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+
+        let token = campaign.token.clone();
+
+        let pledge = self.remove_contribution(campaign_id, &contributor).expect("Nothing to withdraw");
+        let amount = pledge.amount.0;
+
+        let campaign = self.campaigns.get_mut(&campaign_id).unwrap();
+        for tier in &pledge.tiers {
+            if let Some(reward) = campaign.reward_tiers.get_mut(*tier as usize) {
+                reward.claimed -= 1;
+            }
+        }
+        campaign.total_contributions.0 -= amount;
+        campaign.reverse_sponsor_matches(&pledge.matches);
+
+        Self::transfer_funds(&token, contributor.clone(), amount).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_PLEDGE_CALLBACK)
+                .on_pledge_withdrawn(campaign_id, pledge),
+        )
+    }
+
+    // Receipts go once the funds are back with the backer, a failed transfer puts the
+    // pledge back as it was
+    #[private]
+    pub fn on_pledge_withdrawn(&mut self, campaign_id: u64, pledge: Contribution) -> bool {
+        if is_promise_success() {
+            if let Some(profile) = self.users.get_mut(&pledge.contributor) {
+                profile.contributions.retain(|id| *id != campaign_id);
+            }
+            self.burn_receipts(campaign_id, &pledge.contributor);
+            return true;
+        }
+
+        let amount = pledge.amount.0;
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        for tier in &pledge.tiers {
+            if let Some(reward) = campaign.reward_tiers.get_mut(*tier as usize) {
+                reward.claimed += 1;
+            }
+        }
+        campaign.total_contributions.0 += amount;
+        // Sponsors may have matched others since, only what their pledges still allow comes back
+        let matches = campaign.apply_sponsor_matches(&pledge.contributor, amount);
+
+        self.merge_contribution(campaign_id, Contribution { matches, ..pledge });
+        false
+    }
+
+}
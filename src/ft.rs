@@ -0,0 +1,56 @@
+use crate::*;
+
+use near_sdk::serde_json;
+use near_sdk::{env, ext_contract, AccountId, PromiseOrValue};
+
+// NEP-141 methods called on the token accepted by a campaign
+#[allow(dead_code)]
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+// `msg` expected by ft_on_transfer, e.g. `{"campaign_id": 3, "tier": 0}`
+#[near(serializers = [json])]
+pub struct FtContributionMsg {
+    pub campaign_id: u64,
+    pub tier: Option<u32>,
+    pub access_code: Option<String>, // for private campaigns
+}
+
+impl Contract {
+    // Pays out campaign funds in the campaign's currency: NEAR or its NEP-141 token
+    pub(crate) fn transfer_funds(token: &Option<AccountId>, receiver_id: AccountId, amount: u128) -> Promise {
+        match token {
+            Some(token_id) => ext_ft::ext(token_id.clone())
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(receiver_id, U128(amount), None),
+            None => Promise::new(receiver_id).transfer(NearToken::from_yoctonear(amount)),
+        }
+    }
+}
+
+#[near]
+impl Contract {
+
+    // NEP-141 receiver, tokens sent with ft_transfer_call are pledged to the campaign in `msg`
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+        let msg: FtContributionMsg = serde_json::from_str(&msg).expect("Invalid contribution message");
+
+        let campaign = self.campaigns.get(&msg.campaign_id).expect("Campaign does not exist");
+        // Only listed tokens can call in, an account of the creator's choosing could
+        // otherwise pledge in anyone's name and spend their storage balance
+        require!(self.accepted_tokens.contains(&token_id), "Token is not accepted by the DAO");
+        require!(campaign.token.as_ref() == Some(&token_id), "Token is not accepted by this campaign");
+
+        let storage = self.contribution_storage_bytes(msg.campaign_id, &sender_id, &Some(token_id), msg.tier);
+        self.charge_storage_balance(&sender_id, storage::storage_cost(storage));
+        self.internal_contribute(msg.campaign_id, sender_id, amount.0, msg.tier, msg.access_code);
+
+        // The whole amount was used, nothing goes back to the sender
+        PromiseOrValue::Value(U128(0))
+    }
+
+}
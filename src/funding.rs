@@ -0,0 +1,66 @@
+use crate::*;
+
+use near_sdk::AccountId;
+
+// Extra cut taken from keep-it-all campaigns that pay out below their goal
+pub const KEEP_IT_ALL_FEE_BPS: u16 = 500;
+
+// Decides when a campaign may pay out and when its backers get refunded
+#[near(serializers = [json, borsh])]
+#[derive(Clone, Default, PartialEq)]
+pub enum FundingModel {
+    // Funds are released only if the goal is reached, refunded otherwise
+    #[default]
+    AllOrNothing,
+    // The creator keeps whatever was raised
+    KeepItAll,
+    // Funds are released once `min_goal` is reached, even below the full goal
+    Flexible { min_goal: U128 },
+}
+
+impl FundingModel {
+    pub(crate) fn validate(&self, amount_required: U128) {
+        if let FundingModel::Flexible { min_goal } = self {
+            require!(min_goal.0 > 0, "Minimum goal must be positive");
+            require!(min_goal.0 <= amount_required.0, "Minimum goal cannot exceed the funding goal");
+        }
+    }
+}
+
+impl Campaign {
+    // Whether enough was raised for the creator to be paid under the campaign's model
+    pub(crate) fn is_funded(&self) -> bool {
+        let raised = self.total_contributions.0;
+        match &self.funding_model {
+            FundingModel::AllOrNothing => raised >= self.amount_required.0,
+            FundingModel::KeepItAll => raised > 0,
+            FundingModel::Flexible { min_goal } => raised >= min_goal.0,
+        }
+    }
+
+    // Fee owed to the treasury when paying out `amount`, in the campaign's currency:
+    // the platform fee plus the keep-it-all surcharge
+    pub(crate) fn payout_fee(&self, amount: u128, platform_fee_bps: u16) -> u128 {
+        let short_of_goal = self.total_contributions.0 < self.amount_required.0;
+        let mut fee_bps = platform_fee_bps;
+        if self.funding_model == FundingModel::KeepItAll && short_of_goal {
+            fee_bps += KEEP_IT_ALL_FEE_BPS;
+        }
+        mul_div(amount, fee_bps.into(), BPS_DENOMINATOR)
+    }
+}
+
+impl Contract {
+    // Fees from token campaigns are kept per token, next to the NEAR treasury
+    pub(crate) fn collect_fee(&mut self, token: &Option<AccountId>, fee: u128) {
+        match token {
+            Some(token_id) => {
+                let balance = self.token_treasury.entry(token_id.clone()).or_insert(U128(0));
+                balance.0 = balance.0.checked_add(fee).expect("Treasury overflow");
+            }
+            None => {
+                self.treasury = self.treasury.checked_add(NearToken::from_yoctonear(fee)).expect("Treasury overflow");
+            }
+        }
+    }
+}
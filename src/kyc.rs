@@ -1,22 +1,36 @@
 use crate::*;
 
-use near_sdk::{env, AccountId};
+use near_sdk::AccountId;
+
+impl Contract {
+    // Called when a BanUser proposal passes
+    pub(crate) fn internal_ban_user(&mut self, user: AccountId) {
+        self.banned_users.insert(user.clone());
+        // Remove from verified if banned, their pledges stop counting in funding rounds
+        if self.verified_users.remove(&user) {
+            self.requote_backer_rounds(&user, false);
+        }
+    }
+
+    pub(crate) fn require_not_banned(&self, user: &AccountId) {
+        require!(!self.banned_users.contains(user), "User is banned.");
+    }
+}
 
 #[near]
 impl Contract {
 
+    // Only the contract account verifies users, quadratic funding rounds trust this set
+    #[private]
     pub fn verify_user(&mut self, user: AccountId) {
-        assert!(!self.banned_users.contains(&user), "User is banned.");
-        self.verified_users.insert(user);
+        self.require_not_banned(&user);
+        if self.verified_users.insert(user.clone()) {
+            self.requote_backer_rounds(&user, true);
+        }
     }
 
-    // pub fn ban_user(&mut self, user: AccountId) {
-    //     self.banned_users.insert(user);
-    //     self.verified_users.remove(&user.clone()); // Remove from verified if banned
-    // }
-
+    #[private]
     pub fn unban_user(&mut self, user: AccountId) {
         self.banned_users.remove(&user);
     }
 }
-
@@ -0,0 +1,69 @@
+use crate::*;
+
+use near_sdk::env;
+
+impl Campaign {
+    // Published and past its start time
+    pub(crate) fn is_live(&self) -> bool {
+        self.published && env::block_timestamp() >= self.start_time.0
+    }
+}
+
+impl Contract {
+    // Goals above the policy's KYC threshold need a verified creator
+    pub(crate) fn require_goal_allowed(&self, creator: &AccountId, amount_required: U128) {
+        if amount_required.0 > self.policy.kyc_required_above.as_yoctonear() {
+            let profile = self.users.get(creator).expect("User profile not found");
+            require!(profile.kyc_verified, "KYC verification required to create a campaign");
+        }
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Drafts can still be edited, nothing else changes once they are published
+    pub fn edit_campaign(
+        &mut self,
+        campaign_id: u64,
+        title: Option<String>,
+        description: Option<String>,
+        images: Option<String>,
+        amount_required: Option<U128>,
+    ) {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can edit the campaign");
+        require!(!campaign.published, "Only draft campaigns can be edited");
+
+        if let Some(amount_required) = amount_required {
+            self.require_goal_allowed(&campaign.creator, amount_required);
+            campaign.funding_model.validate(amount_required);
+        }
+
+        let campaign = self.campaigns.get_mut(&campaign_id).unwrap();
+        if let Some(title) = title {
+            campaign.title = title;
+        }
+        if let Some(description) = description {
+            campaign.description = description;
+        }
+        if let Some(images) = images {
+            campaign.images = images;
+        }
+        if let Some(amount_required) = amount_required {
+            campaign.amount_required = amount_required;
+        }
+    }
+
+    pub fn publish_campaign(&mut self, campaign_id: u64) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can publish the campaign");
+        require!(!campaign.published, "Campaign is already published");
+        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Campaign has already ended");
+
+        campaign.published = true;
+    }
+
+}
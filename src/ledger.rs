@@ -0,0 +1,93 @@
+use crate::*;
+
+use near_sdk::AccountId;
+
+const DEFAULT_CONTRIBUTIONS_PAGE: u32 = 50;
+
+pub(crate) type Ledger = IterableMap<AccountId, Contribution>;
+
+impl Campaign {
+    pub(crate) fn has_contributions(&self) -> bool {
+        self.total_contributions.0 > 0
+    }
+}
+
+impl Contract {
+    pub(crate) fn ledger_mut(&mut self, campaign_id: u64) -> &mut Ledger {
+        self.campaign_ledgers
+            .entry(campaign_id)
+            .or_insert_with(|| IterableMap::new(Prefix::CampaignLedgerOf(campaign_id)))
+    }
+
+    pub(crate) fn contribution_of(&self, campaign_id: u64, account_id: &AccountId) -> u128 {
+        self.campaign_ledgers
+            .get(&campaign_id)
+            .and_then(|ledger| ledger.get(account_id))
+            .map_or(0, |c| c.amount.0)
+    }
+
+    // What an account has at stake in a campaign: its own contributions plus what it
+    // matched as a sponsor, used for refunds and withdrawal votes
+    pub(crate) fn stake_of(&self, campaign_id: u64, account_id: &AccountId) -> u128 {
+        let matched = self.campaigns.get(&campaign_id).map_or(0, |campaign| campaign.matched_by(account_id));
+        self.contribution_of(campaign_id, account_id) + matched
+    }
+
+    // Adds to the contributor's running total, the campaign total is kept by the caller
+    pub(crate) fn record_contribution(&mut self, campaign_id: u64, contributor: AccountId, amount: u128, token: Option<AccountId>, tier: Option<u32>, matches: Vec<sponsors::SponsorMatch>) {
+        self.merge_contribution(campaign_id, Contribution {
+            contributor,
+            amount: U128(amount),
+            token,
+            tiers: tier.into_iter().collect(),
+            matches,
+        });
+    }
+
+    // Adds `pledge` on top of whatever the backer already gave, also puts back a
+    // contribution that was taken out
+    pub(crate) fn merge_contribution(&mut self, campaign_id: u64, pledge: Contribution) {
+        self.add_round_pledges(campaign_id, &pledge.contributor, pledge.amount.0);
+        let ledger = self.ledger_mut(campaign_id);
+        match ledger.get_mut(&pledge.contributor) {
+            Some(entry) => {
+                entry.amount.0 += pledge.amount.0;
+                entry.tiers.extend(pledge.tiers);
+                sponsors::merge_matches(&mut entry.matches, pledge.matches);
+            }
+            None => {
+                ledger.insert(pledge.contributor.clone(), pledge);
+            }
+        }
+    }
+
+    // Takes a backer's whole entry out of the ledger
+    pub(crate) fn remove_contribution(&mut self, campaign_id: u64, contributor: &AccountId) -> Option<Contribution> {
+        let entry = self.campaign_ledgers.get_mut(&campaign_id)?.remove(contributor)?;
+        self.remove_round_pledges(campaign_id, contributor);
+        Some(entry)
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn get_campaign_contributions(&self, campaign_id: u64, from_index: Option<u32>, limit: Option<u32>) -> Vec<&Contribution> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaign_ledgers
+            .get(&campaign_id)
+            .map(|ledger| {
+                ledger
+                    .values()
+                    .skip(from_index.unwrap_or(0) as usize)
+                    .take(limit.unwrap_or(DEFAULT_CONTRIBUTIONS_PAGE) as usize)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_campaign_backer_count(&self, campaign_id: u64) -> u32 {
+        self.campaign_ledgers.get(&campaign_id).map_or(0, |ledger| ledger.len())
+    }
+
+}
@@ -1,12 +1,33 @@
 // use near_sdk::{log, near};
-use near_sdk::{env, Gas, log, near, require, AccountId, NearToken, PanicOnDefault, Promise};
-use near_sdk::json_types::U64;
+use near_sdk::{env, near, require, AccountId, Gas, NearToken, PanicOnDefault, Promise};
+use near_sdk::json_types::{U128, U64};
 // use near_sdk::borsh::{self, BorshSerialize, BorshDeserialize};
-use near_sdk::store::{LookupMap, IterableMap, IterableSet, Vector};
+use near_sdk::store::{IterableMap, IterableSet, LookupMap, LookupSet, Vector};
 use near_sdk::BorshStorageKey;
 
+mod access;
+mod beneficiary;
+mod comments;
 mod dao;
+mod extensions;
+mod ft;
+mod funding;
 mod kyc;
+mod launch;
+mod ledger;
+mod migration;
+mod milestones;
+mod receipts;
+mod refunds;
+mod rewards;
+mod rounds;
+mod sponsors;
+mod storage;
+mod subscriptions;
+mod teams;
+mod updates;
+mod upgrade;
+mod voting;
 
 #[near]
 #[derive(BorshStorageKey)]
@@ -14,15 +35,89 @@ pub enum Prefix {
     Root,
     Vector,
     LookupSet,
-    IterableSet,
+    IterableSet, // shared by verified_users and banned_users before they got their own
     LookupMap,
-    IterableMap,
+    IterableMap, // shared by the campaign, user, loan and proposal maps before they got their own
     Nested(String),
+    RefundClaims,
+    Receipts,
+    ReceiptsPerOwner,
+    FundingRounds,
+    Subscriptions,
+    CampaignUpdates,
+    CampaignUpdatesOf(u64),
+    CampaignCodes,
+    CampaignComments,
+    CampaignCommentsOf(u64),
+    CampaignLedgers,
+    CampaignLedgerOf(u64),
+    Campaigns,
+    Users,
+    LoanRequests,
+    Loans,
+    Proposals,
+    VerifiedUsers,
+    BannedUsers,
+    StagedCode,
+    FrozenCampaigns,
+    WithdrawalVotes,
+    StorageBalances,
+    TokenTreasury,
+    ProposalBallots,
+    AcceptedTokens,
+    UnpaidPayouts,
+    RoundPledges,
+    CampaignRounds,
+    SubscriptionsPerCreator,
+    SubscriptionsPerBacker,
 }
 
 //GAS constants to attach to calls
-const GAS_FOR_WITHDRAWALE: Gas = Gas::from_tgas(115);
+const GAS_FOR_REFUND_CALLBACK: Gas = Gas::from_tgas(10);
+const GAS_FOR_PLEDGE_CALLBACK: Gas = Gas::from_tgas(20);
+const GAS_FOR_PAYOUT_CALLBACK: Gas = Gas::from_tgas(10);
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(10);
+const GAS_FOR_NFT_ON_TRANSFER: Gas = Gas::from_tgas(25);
+const GAS_FOR_NFT_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+
+// Campaigns asking for more than this need a KYC verified creator, until the DAO changes its policy
+const KYC_REQUIRED_ABOVE: NearToken = NearToken::from_near(5_000_000);
+
+// Fees, shares and ratios are expressed in basis points
+pub(crate) const BPS_DENOMINATOR: u128 = 10_000;
+
+// a * b / d without overflowing on yoctoNEAR sized products, result must fit in u128
+pub(crate) fn mul_div(a: u128, b: u128, d: u128) -> u128 {
+    require!(d > 0, "Division by zero");
+    if let Some(product) = a.checked_mul(b) {
+        return product / d;
+    }
 
+    // 256 bit product split into high and low halves
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (lo_lo & MASK) | (mid << 64);
+    let hi = a_hi * b_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    require!(hi < d, "Arithmetic overflow");
+
+    // Long division of the 256 bit product by d
+    let (mut remainder, mut quotient) = (hi, 0u128);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= d {
+            remainder = remainder.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    quotient
+}
 
 #[near(contract_state)]
 #[derive(PanicOnDefault)]
@@ -34,13 +129,36 @@ pub struct Contract {
     pub loans: IterableMap<u64, Loan>,
     pub next_loan_request_id: u64,
     pub next_loan_id: u64,
-    treasury: u64,
+    treasury: NearToken,
     proposals: IterableMap<u64, dao::Proposal>,
     trusted_members: Vector<AccountId>,
     proposal_count: u64,
     pub verified_users: IterableSet<AccountId>, // Set of verified users
     pub banned_users: IterableSet<AccountId>, 
-    
+    refund_claims: LookupSet<(u64, AccountId)>, // (campaign id, contributor) pairs already refunded
+    receipts: IterableMap<u64, receipts::ContributionReceipt>, // NEP-171 receipts keyed by token id
+    receipts_per_owner: LookupMap<AccountId, Vec<u64>>,
+    next_receipt_id: u64,
+    platform_fee_bps: u16, // taken from payouts to creators, set through DAO proposals
+    proposal_votes: LookupMap<(u64, AccountId), bool>, // each member's vote, counted while they stay a member
+    funding_rounds: Vector<rounds::FundingRound>,
+    subscriptions: IterableMap<u64, subscriptions::Subscription>,
+    next_subscription_id: u64,
+    campaign_updates: LookupMap<u64, Vector<updates::CampaignUpdate>>,
+    campaign_codes: LookupMap<String, u64>, // public campaign codes to campaign ids
+    campaign_comments: LookupMap<u64, Vector<comments::Comment>>,
+    campaign_ledgers: LookupMap<u64, ledger::Ledger>, // per-campaign contributions keyed by contributor
+    policy: dao::Policy,
+    frozen_campaigns: LookupSet<u64>, // campaigns frozen by the DAO
+    withdrawal_votes: LookupSet<(u64, u32, AccountId)>, // (campaign id, request id, backer) triples that already voted
+    storage_balances: LookupMap<AccountId, NearToken>, // prepaid storage for token contributions
+    token_treasury: LookupMap<AccountId, U128>, // fees collected from token campaigns, by token
+    accepted_tokens: LookupSet<AccountId>, // NEP-141 tokens campaigns may raise in, set by the DAO
+    unpaid_payouts: LookupMap<(u64, AccountId), U128>, // (campaign id, recipient) payout shares whose transfer failed
+    round_pledges: LookupMap<(u32, u64, AccountId), U128>, // (round id, campaign id, backer) what was given while in the round
+    campaign_rounds: LookupMap<u64, Vec<u32>>, // funding rounds each campaign joined
+    subscriptions_per_creator: LookupMap<AccountId, Vec<u64>>,
+    subscriptions_per_backer: LookupMap<AccountId, Vec<u64>>,
 }
 
 
@@ -49,7 +167,7 @@ pub struct Contract {
 #[derive(Clone)]
 pub struct LoanRequest {
     pub borrower: AccountId,
-    pub amount: u64,
+    pub amount: NearToken,
     pub interest_rate: u8, // interest rate as a percentage
     pub duration: U64, // loan duration in seconds
     pub fulfilled: bool,
@@ -62,7 +180,7 @@ pub struct Loan {
     pub loan_id: u64,
     pub borrower: AccountId,
     pub lender: AccountId,
-    pub amount: u64,
+    pub amount: NearToken,
     pub interest_rate: u8,
     pub duration: U64,
     pub start_time: U64,
@@ -74,15 +192,15 @@ pub struct Loan {
 pub struct WithdrawalLog {
     pub campaign_id: u64,
     pub withdrawn_by: AccountId,
-    pub amount: u64,
+    pub amount: U128,
     pub timestamp: U64,
 }
 
 #[near(serializers = [json, borsh])]
 #[derive(Clone, Default)]
 pub struct CampaignStats {
-    pub total_funds: u64,
-    pub total_withdrawn: u64,
+    pub total_funds: U128,
+    pub total_withdrawn: U128,
     pub number_of_withdrawals: u64,
 }
 
@@ -90,15 +208,35 @@ pub struct CampaignStats {
 #[derive(Clone)]
 pub struct Campaign {
     pub creator: AccountId,
-    pub total_contributions: u64,
-    pub contributions: Vec<Contribution>,
+    pub total_contributions: U128,
     pub crowdfunding_end_time: U64,
     pub claimed: bool,
-    pub amount_required : u64,
+    pub amount_required : U128,
     pub title : String,
     pub description : String,
     pub images : String,
-    pub campaign_code : String
+    pub campaign_code : String,
+    pub milestones: Vec<milestones::Milestone>, // empty when funds are paid out in one shot
+    pub released: U128, // funds already paid out of escrow
+    pub withdrawal_request: Option<voting::WithdrawalRequest>,
+    pub refunds_open: bool, // set once the campaign is cancelled, fails or backers reject a withdrawal
+    pub token: Option<AccountId>, // NEP-141 token accepted instead of NEAR
+    pub reward_tiers: Vec<rewards::RewardTier>,
+    pub transferable_receipts: bool, // receipts are soulbound unless the creator opts in
+    pub funding_model: funding::FundingModel,
+    pub extensions: u8, // times the deadline was pushed back
+    pub opt_out_ends_at: Option<U64>, // backers may withdraw their pledge until then after an extension
+    pub sponsor_pledges: Vec<sponsors::SponsorPledge>,
+    pub team: Vec<teams::TeamMember>, // payouts are split between members when set
+    pub beneficiary: Option<AccountId>, // receives the payouts instead of the creator, e.g. a charity
+    pub beneficiary_accepted: bool,
+    pub start_time: U64, // contributions open at this time, 0 to open on publication
+    pub published: bool, // drafts can be edited but accept no contributions
+    pub private: bool, // hidden from listings, pledges need the access code or the allowlist
+    pub access_code_hash: Option<String>,
+    pub allowlist: Vec<AccountId>,
+    pub pinned_comments: Vec<u32>,
+    pub withdrawal_requests: u32, // opened so far, the next request takes this id
 }
 
 #[near(serializers = [json, borsh])]
@@ -111,12 +249,15 @@ pub struct UserProfile {
     pub created_campaigns: Vec<u64>, // Campaign IDs created by the user
 }
 
-// Defining a very simple structure for a contribution
+// Everything an account contributed to a campaign
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct Contribution {
     pub contributor: AccountId,
-    pub amount: u64,
+    pub amount: U128,
+    pub token: Option<AccountId>, // None for NEAR contributions
+    pub tiers: Vec<u32>, // reward tier claimed by each pledge that selected one
+    pub matches: Vec<sponsors::SponsorMatch>, // what sponsors added on top, per sponsor
 }
 
 
@@ -127,28 +268,53 @@ impl Contract {
     #[init]
     #[private] // only callable by the contract's account
     pub fn init() -> Self {
+        migration::write_state_version();
         Self {
             // campaigns: Vec::new(),
-            campaigns : IterableMap::new(Prefix::IterableMap), 
-            users: IterableMap::new(Prefix::IterableMap),
+            campaigns : IterableMap::new(Prefix::Campaigns), 
+            users: IterableMap::new(Prefix::Users),
             next_campaign_id: 0,
-            loan_requests : IterableMap::new(Prefix::IterableMap),
-            loans : IterableMap::new(Prefix::IterableMap),
+            loan_requests : IterableMap::new(Prefix::LoanRequests),
+            loans : IterableMap::new(Prefix::Loans),
             next_loan_id : 0,
             next_loan_request_id : 0,
-            treasury : 0,
-            proposals : IterableMap::new(Prefix::IterableMap),
+            treasury : NearToken::from_yoctonear(0),
+            proposals : IterableMap::new(Prefix::Proposals),
             trusted_members : Vector::new(Prefix::Vector),
             proposal_count : 0,
-            verified_users : IterableSet::new(Prefix::IterableSet),
-            banned_users : IterableSet::new(Prefix::IterableSet),
+            verified_users : IterableSet::new(Prefix::VerifiedUsers),
+            banned_users : IterableSet::new(Prefix::BannedUsers),
+            refund_claims: LookupSet::new(Prefix::RefundClaims),
+            receipts: IterableMap::new(Prefix::Receipts),
+            receipts_per_owner: LookupMap::new(Prefix::ReceiptsPerOwner),
+            next_receipt_id: 0,
+            platform_fee_bps: dao::DEFAULT_PLATFORM_FEE_BPS,
+            proposal_votes: LookupMap::new(Prefix::ProposalBallots),
+            funding_rounds: Vector::new(Prefix::FundingRounds),
+            subscriptions: IterableMap::new(Prefix::Subscriptions),
+            next_subscription_id: 0,
+            campaign_updates: LookupMap::new(Prefix::CampaignUpdates),
+            campaign_codes: LookupMap::new(Prefix::CampaignCodes),
+            campaign_comments: LookupMap::new(Prefix::CampaignComments),
+            campaign_ledgers: LookupMap::new(Prefix::CampaignLedgers),
+            policy: dao::Policy::default(),
+            frozen_campaigns: LookupSet::new(Prefix::FrozenCampaigns),
+            withdrawal_votes: LookupSet::new(Prefix::WithdrawalVotes),
+            storage_balances: LookupMap::new(Prefix::StorageBalances),
+            token_treasury: LookupMap::new(Prefix::TokenTreasury),
+            accepted_tokens: LookupSet::new(Prefix::AcceptedTokens),
+            unpaid_payouts: LookupMap::new(Prefix::UnpaidPayouts),
+            round_pledges: LookupMap::new(Prefix::RoundPledges),
+            campaign_rounds: LookupMap::new(Prefix::CampaignRounds),
+            subscriptions_per_creator: LookupMap::new(Prefix::SubscriptionsPerCreator),
+            subscriptions_per_backer: LookupMap::new(Prefix::SubscriptionsPerBacker),
             // withdrawal_logs : IterableMap::new(Prefix::IterableMap),
             // campaign_stats : IterableMap::new(Prefix::IterableMap)
         }
     }
 
     #[payable]
-    pub fn create_loan_request(&mut self, amount: u64, interest_rate: u8, duration: U64) -> u64 {
+    pub fn create_loan_request(&mut self, amount: NearToken, interest_rate: u8, duration: U64) -> u64 {
         let borrower = env::predecessor_account_id();
         let loan_request_id = self.next_loan_request_id;
         
@@ -174,7 +340,7 @@ impl Contract {
         
         // Ensure loan request is open and not yet fulfilled
         require!(!loan_request.fulfilled, "Loan request is already fulfilled");
-        require!(env::attached_deposit() >= NearToken::from_yoctonear(loan_request.amount.into()), "Insufficient deposit to fulfill loan request");
+        require!(env::attached_deposit() >= loan_request.amount, "Insufficient deposit to fulfill loan request");
 
         // Mark the loan request as fulfilled
         loan_request.fulfilled = true;
@@ -209,11 +375,11 @@ impl Contract {
         require!(!loan.repaid, "Loan has already been repaid");
 
         // Calculate repayment amount (principal + interest)
-        let interest_amount = loan.amount * loan.interest_rate as u64 / 100;
-        let total_repayment = loan.amount + interest_amount;
+        let interest_amount = NearToken::from_yoctonear(loan.amount.as_yoctonear() * loan.interest_rate as u128 / 100);
+        let total_repayment = loan.amount.saturating_add(interest_amount);
 
         require!(
-            env::attached_deposit() >= NearToken::from_yoctonear(total_repayment.into()),
+            env::attached_deposit() >= total_repayment,
             "Insufficient amount to repay loan"
         );
 
@@ -222,7 +388,7 @@ impl Contract {
         // self.loans.insert(loan_id, &loan);
 
         // Transfer funds to lender
-        Promise::new(loan.lender.clone()).transfer(NearToken::from_yoctonear(total_repayment.into()));
+        Promise::new(loan.lender.clone()).transfer(total_repayment).detach();
     }
 
     pub fn get_loan_request(&self, loan_request_id: u64) -> LoanRequest {
@@ -251,29 +417,64 @@ impl Contract {
 
     // Campaigns
 
-    pub fn create_campaign(&mut self, end_time: U64, title : String, description : String, images : String, amount_required : u64, campaign_code : String) {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_campaign(&mut self, end_time: U64, title : String, description : String, images : String, amount_required : U128, campaign_code : String, milestones: Option<Vec<milestones::NewMilestone>>, token: Option<AccountId>, funding_model: Option<funding::FundingModel>, beneficiary: Option<AccountId>, start_time: Option<U64>, draft: Option<bool>) -> u64 {
         let creator = env::predecessor_account_id();
+        self.require_not_banned(&creator);
+        self.require_goal_allowed(&creator, amount_required);
         let profile = self.users.get_mut(&creator).expect("User profile not found");
-        if amount_required > 5000000 {
-            // Ensure the user is KYC verified
-            require!(profile.kyc_verified, "KYC verification required to create a campaign");
+        let milestones = milestones.map(|m| milestones::build_milestones(m, end_time)).unwrap_or_default();
+        if let Some(token_id) = &token {
+            require!(self.accepted_tokens.contains(token_id), "Token is not accepted by the DAO");
         }
-        self.campaigns.insert(self.next_campaign_id, Campaign {
+        let funding_model = funding_model.unwrap_or_default();
+        funding_model.validate(amount_required);
+        // Creators naming themselves need no acceptance
+        let beneficiary = beneficiary.filter(|b| b != &creator);
+        let start_time = start_time.unwrap_or(U64(0));
+        require!(start_time.0 < end_time.0, "Campaign must start before it ends");
+        let campaign_id = self.next_campaign_id;
+        if !campaign_code.is_empty() {
+            require!(!self.campaign_codes.contains_key(&campaign_code), "Campaign code is already taken");
+            self.campaign_codes.insert(campaign_code.clone(), campaign_id);
+        }
+        self.campaigns.insert(campaign_id, Campaign {
             creator: creator.clone(),
-            total_contributions: 0,
-            contributions: Vec::new(),
+            total_contributions: U128(0),
             crowdfunding_end_time: end_time,
             claimed: false,
-            amount_required : amount_required,
-            title : title,
+            amount_required,
+            title,
             description : description.to_string(),
             images : images.to_string(),
-            campaign_code : campaign_code
+            campaign_code,
+            milestones,
+            released: U128(0),
+            withdrawal_request: None,
+            refunds_open: false,
+            token,
+            reward_tiers: Vec::new(),
+            transferable_receipts: false,
+            funding_model,
+            extensions: 0,
+            opt_out_ends_at: None,
+            sponsor_pledges: Vec::new(),
+            team: Vec::new(),
+            beneficiary,
+            beneficiary_accepted: false,
+            start_time,
+            published: !draft.unwrap_or(false),
+            private: false,
+            access_code_hash: None,
+            allowlist: Vec::new(),
+            pinned_comments: Vec::new(),
+            withdrawal_requests: 0,
         },);
-        profile.created_campaigns.push(self.next_campaign_id);
+        profile.created_campaigns.push(campaign_id);
         // self.users.insert(creator, profile);
+        self.next_campaign_id += 1;
 
-
+        campaign_id
     }
 
     #[payable]
@@ -323,35 +524,55 @@ impl Contract {
     }
 
     #[payable]
-    pub fn contribute(&mut self, campaign_id : u64) {
+    pub fn contribute(&mut self, campaign_id : u64, tier: Option<u32>, access_code: Option<String>) {
         // require!(campaign_index < self.campaigns.len(), "Campaign does not exist");
         require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        require!(self.campaigns.get(&campaign_id).unwrap().token.is_none(), "Campaign only accepts contributions in its token");
         
         
-        // Get the amount contributed
-        let amount = env::attached_deposit();
         let contributor = env::predecessor_account_id();
 
+        // The deposit pays for the contribution's storage, the rest is contributed
+        let storage = storage::storage_cost(self.contribution_storage_bytes(campaign_id, &contributor, &None, tier));
+        let deposit = env::attached_deposit();
+        require!(deposit > storage, format!("Attach more than {} to cover the contribution's storage", storage));
+
+        self.internal_contribute(campaign_id, contributor, deposit.saturating_sub(storage).as_yoctonear(), tier, access_code);
+    }
+
+    // Records a contribution made in the campaign's currency, either NEAR or its token
+    fn internal_contribute(&mut self, campaign_id: u64, contributor: AccountId, amount: u128, tier: Option<u32>, access_code: Option<String>) {
+        self.require_not_banned(&contributor);
+        self.require_not_frozen(campaign_id);
         let profile = self.users.get_mut(&contributor).expect("User profile not found");
 
         // let campaign = &mut self.campaigns[campaign_index];
         let campaign = self.campaigns.get_mut(&campaign_id).unwrap();
 
         // Assert the crowdfunding is still ongoing
+        require!(campaign.is_live(), "Campaign has not launched yet");
+        campaign.require_access(&contributor, access_code.as_deref());
         require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Crowdfunding has ended");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+        require!(amount > 0, "Contribution must be positive");
 
-        // Record the contribution
-        campaign.contributions.push(Contribution {
-            contributor,
-            amount: amount.as_near() as u64,
-        });
+        if let Some(tier) = tier {
+            campaign.claim_reward_tier(tier, amount);
+        }
 
         // Update the total contributions
-        campaign.total_contributions += amount.as_near() as u64;
+        campaign.total_contributions.0 += amount;
+        let matches = campaign.apply_sponsor_matches(&contributor, amount);
+        let token = campaign.token.clone();
 
         if !profile.contributions.contains(&campaign_id) {
             profile.contributions.push(campaign_id);
         }
+
+        // Record the contribution with whatever sponsors matched
+        self.record_contribution(campaign_id, contributor.clone(), amount, token.clone(), tier, matches);
+
+        self.mint_receipt(campaign_id, contributor, amount, token, tier);
     }
 
    
@@ -359,26 +580,31 @@ impl Contract {
     pub fn withdraw(&mut self, campaign_id : u64) -> Promise {
         // require!(campaign_index < self.campaigns.len(), "Campaign does not exist");
         require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.require_not_frozen(campaign_id);
 
         // let campaign = &mut self.campaigns[campaign_index];
         let campaign = self.campaigns.get_mut(&campaign_id).unwrap();
 
         require!(env::block_timestamp() > campaign.crowdfunding_end_time.into(), "Crowdfunding has not ended yet");
         require!(!campaign.claimed, "Funds have already been claimed");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
 
-        require!(campaign.total_contributions >= campaign.amount_required, "Campaign has not reached its funding goal");
+        require!(campaign.is_funded(), "Campaign has not reached its funding goal");
+        require!(campaign.milestones.is_empty(), "Campaign funds are released through milestones");
+        campaign.require_payable();
 
         campaign.claimed = true;
 
-        // Transfer total contributions to the project creator
-        let promise = Promise::new(campaign.creator.clone()).transfer(NearToken::from_yoctonear(campaign.total_contributions.into()));
+        let total = campaign.total_contributions.0;
+        let fee = campaign.payout_fee(total, self.platform_fee_bps);
+        let token = campaign.token.clone();
+
+        // Transfer total contributions to the project team
+        let promise = Self::pay_out(campaign_id, campaign, total - fee);
         // self.campaigns.insert(campaign_id, campaign.clone()); // Update campaign state
+        self.collect_fee(&token, fee);
         promise
     }
-    
-    pub fn transfer_token(&self, to : AccountId, amount : NearToken) {
-        Promise::new(to).transfer(amount);
-    } 
 
     pub fn get_campaign(&self, campaign_id: u64) -> Campaign {
         require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
@@ -386,107 +612,113 @@ impl Contract {
     }
 
     pub fn get_all_campaigns_deprecated(&self) -> Vec<&Campaign> {
-        self.campaigns.iter().map(|(_, campaign)| campaign).collect() // Collect all campaigns
+        self.campaigns.iter().map(|(_, campaign)| campaign).filter(|campaign| !campaign.private).collect() // Collect all public campaigns
     }
 
     pub fn get_all_the_campaign_deprecated(&self) -> Vec<(&u64, &Campaign)> {
-        self.campaigns.iter().collect()
+        self.campaigns.iter().filter(|(_, campaign)| !campaign.private).collect()
     }
 
+    // Private campaigns are left out, members reach them by id
     pub fn get_all_campaigns(&self, from_index: i32, limit: i32) -> Vec<(&u64, &Campaign)> {
         self.campaigns
             .iter()
+            .filter(|(_, campaign)| !campaign.private)
             .skip(from_index as usize)
             .take(limit as usize)
             .collect()
     }
 
+    pub fn get_campaign_by_code(&self, campaign_code: String) -> Option<(u64, Campaign)> {
+        let campaign_id = *self.campaign_codes.get(&campaign_code)?;
+        self.campaigns.get(&campaign_id).map(|campaign| (campaign_id, campaign.clone()))
+    }
+
     pub fn get_campaign_status(&self, campaign_id: u64) -> String {
         require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
     
         let campaign = self.campaigns.get(&campaign_id).unwrap();
         let current_time = env::block_timestamp();
     
-        if campaign.claimed {
+        if self.frozen_campaigns.contains(&campaign_id) {
+            "Frozen".to_string()
+        } else if !campaign.published {
+            "Draft".to_string()
+        } else if campaign.claimed {
             "Funds claimed".to_string()
+        } else if campaign.refunds_open || campaign.milestone_overdue() {
+            "Refunds open".to_string()
+        } else if campaign.withdrawal_request.is_some() {
+            "Withdrawal vote in progress".to_string()
+        } else if campaign.released.0 > 0 {
+            "Releasing milestones".to_string()
         } else if current_time > campaign.crowdfunding_end_time.into() {
-            "Crowdfunding ended".to_string()
-        } else if campaign.total_contributions >= campaign.amount_required {
+            if campaign.is_funded() {
+                "Crowdfunding ended".to_string()
+            } else {
+                "Funding goal not met".to_string()
+            }
+        } else if current_time < campaign.start_time.0 {
+            "Scheduled".to_string()
+        } else if campaign.total_contributions.0 >= campaign.amount_required.0 {
             "Funding goal reached".to_string()
+        } else if matches!(campaign.funding_model, funding::FundingModel::Flexible { .. }) && campaign.is_funded() {
+            "Minimum goal reached".to_string()
         } else {
             "Crowdfunding active".to_string()
         }
     }
 
-    pub fn get_campaign_contributions(&self, campaign_id: u64) -> Vec<Contribution> {
-        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
-        self.campaigns.get(&campaign_id).unwrap().contributions.clone()
-    }
-
     pub fn cancel_campaign(&mut self, campaign_id: u64) {
-        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
     
         require!(env::predecessor_account_id() == campaign.creator, "Only the creator can cancel the campaign");
-        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Cannot cancel after the end time");
-    
-        // Refund contributions amount back to each contributor
-        for contribution in &campaign.contributions {
-            Promise::new(contribution.contributor.clone()).transfer(NearToken::from_yoctonear(campaign.total_contributions.into()));
-        }
+        // Past the end time only a payout stuck waiting on its recipients can be called off
+        require!(
+            env::block_timestamp() < campaign.crowdfunding_end_time.into() || (!campaign.claimed && !campaign.is_payable()),
+            "Cannot cancel after the end time"
+        );
     
-        self.campaigns.remove(&campaign_id);
+        // Contributors get their contributions back through claim_refund
+        campaign.refunds_open = true;
     }
 
     // pub fn get_user_campaigns(&self, user_id: AccountId) -> Vec<Campaign> {
     //     self.campaigns.iter().filter(|(_, campaign)| campaign.creator == user_id).map(|(_, campaign)| campaign).collect()
     // }
 
-    // pub fn extend_campaign(&mut self, campaign_id: u64, additional_time: U64) {
-    //     let mut campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
-    
-    //     require!(env::predecessor_account_id() == campaign.creator, "Only the creator can extend the campaign");
-    //     require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Campaign has already ended");
-    
-    //     campaign.crowdfunding_end_time += additional_time;
-    //     let endtime: U64 = U64::from(campaign.crowdfunding_end_time);
-    //     let addtional = U64::from(additional_time);
-    //     let newtime : u64 = endtime.clone() + addtional.clone();
-    //     self.campaigns.insert(&campaign_id, &campaign);
-    // }
-
-    pub fn modify_funding_goal(&mut self, campaign_id: u64, new_goal: u64) {
-        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+    pub fn modify_funding_goal(&mut self, campaign_id: u64, new_goal: U128) {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
     
         require!(env::predecessor_account_id() == campaign.creator, "Only the creator can modify the funding goal");
-        require!(campaign.contributions.is_empty(), "Cannot modify the goal after contributions");
+        require!(!campaign.has_contributions(), "Cannot modify the goal after contributions");
+        campaign.funding_model.validate(new_goal);
+        self.require_goal_allowed(&campaign.creator, new_goal);
     
-        campaign.amount_required = new_goal;
+        self.campaigns.get_mut(&campaign_id).unwrap().amount_required = new_goal;
     }
 
     pub fn refund_contributors(&mut self, campaign_id: u64) {
-        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
     
         require!(env::block_timestamp() > campaign.crowdfunding_end_time.into(), "Campaign is still active");
-        require!(campaign.total_contributions < campaign.amount_required, "Funding goal met; cannot refund");
+        require!(!campaign.is_funded(), "Funding goal met; cannot refund");
     
-        for contribution in &campaign.contributions {
-            Promise::new(contribution.contributor.clone()).transfer(NearToken::from_yoctonear(campaign.total_contributions.into()));
-        }
-    
-        self.campaigns.remove(&campaign_id);
+        // Each contributor pulls their own refund with claim_refund
+        campaign.refunds_open = true;
     }
 
-    pub fn get_user_total_contributions(&self, user_id: AccountId) -> u64 {
-        self.campaigns.iter()
-            .flat_map(|(_, campaign)| campaign.contributions.iter())
-            .filter(|contribution| contribution.contributor == user_id)
-            .map(|contribution| contribution.amount)
-            .sum()
+    // Total contributed in NEAR, token contributions are not comparable across campaigns
+    pub fn get_user_total_contributions(&self, user_id: AccountId) -> U128 {
+        U128(self.campaigns.iter()
+            .filter(|(_, campaign)| campaign.token.is_none())
+            .map(|(campaign_id, _)| self.contribution_of(*campaign_id, &user_id))
+            .sum())
     }
 
-    pub fn get_user_contribution_to_campaign(&self, campaign_id: u64, user_id: AccountId) -> u64 {
-        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
-        campaign.contributions.iter().filter(|c| c.contributor == user_id).map(|c| c.amount).sum()
+    pub fn get_user_contribution_to_campaign(&self, campaign_id: u64, user_id: AccountId) -> U128 {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        U128(self.contribution_of(campaign_id, &user_id))
     }
     
     
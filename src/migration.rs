@@ -0,0 +1,439 @@
+use crate::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, AccountId, IntoStorageKey};
+
+// Layout version of the state written by this code. Bump it whenever `Contract` or a
+// stored value changes shape once a version has been deployed, keep the deployed layout
+// as a `...V<n>` struct and add the step from it to `VersionedState`.
+pub const STATE_VERSION: u32 = 1;
+
+// Holds the state version, deployments from before versioning don't have it
+const VERSION_KEY: &[u8] = b"VERSION";
+const STATE_KEY: &[u8] = b"STATE";
+
+// The first deployment recorded campaign amounts and the treasury in whole NEAR
+const ONE_NEAR: u128 = NearToken::from_near(1).as_yoctonear();
+
+pub(crate) fn write_state_version() {
+    env::storage_write(VERSION_KEY, &borsh::to_vec(&STATE_VERSION).unwrap());
+}
+
+// Contract state as laid out by each version
+enum VersionedState {
+    V0(Box<ContractV0>),
+    V1(Box<Contract>),
+}
+
+impl VersionedState {
+    fn read() -> Self {
+        let raw = env::storage_read(STATE_KEY).expect("No contract state to migrate");
+
+        match stored_version() {
+            // The first deployment wrote no version
+            None => Self::V0(Box::new(ContractV0::try_from_slice(&raw).expect("Cannot read version 0 state"))),
+            Some(1) => Self::V1(Box::new(Contract::try_from_slice(&raw).expect("Cannot read version 1 state"))),
+            Some(version) => env::panic_str(&format!("Unknown state version {}", version)),
+        }
+    }
+}
+
+fn stored_version() -> Option<u32> {
+    env::storage_read(VERSION_KEY).map(|v| u32::try_from_slice(&v).expect("Invalid state version"))
+}
+
+// State as deployed before amounts moved to yoctoNEAR. Collection handles only hold
+// their prefix and length, so they are read with the current value types while the
+// values themselves are upgraded and moved to their own prefixes by `migrate`.
+#[near(serializers = [borsh])]
+pub struct ContractV0 {
+    campaigns: IterableMap<u64, Campaign>,
+    users: IterableMap<AccountId, UserProfile>,
+    next_campaign_id: u64,
+    loan_requests: IterableMap<u64, LoanRequest>,
+    loans: IterableMap<u64, Loan>,
+    next_loan_request_id: u64,
+    next_loan_id: u64,
+    treasury: u64,
+    proposals: IterableMap<u64, dao::Proposal>,
+    trusted_members: Vector<AccountId>,
+    proposal_count: u64,
+    verified_users: IterableSet<AccountId>,
+    banned_users: IterableSet<AccountId>,
+}
+
+#[near(serializers = [borsh])]
+pub struct CampaignV0 {
+    creator: AccountId,
+    total_contributions: u64,
+    contributions: Vec<ContributionV0>,
+    crowdfunding_end_time: U64,
+    claimed: bool,
+    amount_required: u64,
+    title: String,
+    description: String,
+    images: String,
+    campaign_code: String,
+}
+
+#[near(serializers = [borsh])]
+pub struct ContributionV0 {
+    contributor: AccountId,
+    amount: u64,
+}
+
+#[near(serializers = [borsh])]
+pub struct LoanRequestV0 {
+    borrower: AccountId,
+    amount: u64,
+    interest_rate: u8,
+    duration: U64,
+    fulfilled: bool,
+}
+
+#[near(serializers = [borsh])]
+pub struct LoanV0 {
+    loan_id: u64,
+    borrower: AccountId,
+    lender: AccountId,
+    amount: u64,
+    interest_rate: u8,
+    duration: U64,
+    start_time: U64,
+    repaid: bool,
+}
+
+#[near(serializers = [borsh])]
+pub struct ProposalV0 {
+    id: u64,
+    proposer: AccountId,
+    description: String,
+    votes_for: u64,
+    votes_against: u64,
+    executed: bool,
+}
+
+impl From<CampaignV0> for Campaign {
+    fn from(old: CampaignV0) -> Self {
+        Self {
+            creator: old.creator,
+            total_contributions: U128(old.total_contributions as u128 * ONE_NEAR),
+            crowdfunding_end_time: old.crowdfunding_end_time,
+            claimed: old.claimed,
+            amount_required: U128(old.amount_required as u128 * ONE_NEAR),
+            title: old.title,
+            description: old.description,
+            images: old.images,
+            campaign_code: old.campaign_code,
+            milestones: Vec::new(),
+            released: U128(0),
+            withdrawal_request: None,
+            refunds_open: false,
+            token: None,
+            reward_tiers: Vec::new(),
+            transferable_receipts: false,
+            funding_model: funding::FundingModel::AllOrNothing,
+            extensions: 0,
+            opt_out_ends_at: None,
+            sponsor_pledges: Vec::new(),
+            team: Vec::new(),
+            beneficiary: None,
+            beneficiary_accepted: false,
+            start_time: U64(0),
+            published: true,
+            private: false,
+            access_code_hash: None,
+            allowlist: Vec::new(),
+            pinned_comments: Vec::new(),
+            withdrawal_requests: 0,
+        }
+    }
+}
+
+// Loans were already stored in yoctoNEAR, only the type changes
+impl From<LoanRequestV0> for LoanRequest {
+    fn from(old: LoanRequestV0) -> Self {
+        Self {
+            borrower: old.borrower,
+            amount: NearToken::from_yoctonear(old.amount.into()),
+            interest_rate: old.interest_rate,
+            duration: old.duration,
+            fulfilled: old.fulfilled,
+        }
+    }
+}
+
+impl From<LoanV0> for Loan {
+    fn from(old: LoanV0) -> Self {
+        Self {
+            loan_id: old.loan_id,
+            borrower: old.borrower,
+            lender: old.lender,
+            amount: NearToken::from_yoctonear(old.amount.into()),
+            interest_rate: old.interest_rate,
+            duration: old.duration,
+            start_time: old.start_time,
+            repaid: old.repaid,
+        }
+    }
+}
+
+// Proposals made before typed proposals were plain text
+impl From<ProposalV0> for dao::Proposal {
+    fn from(old: ProposalV0) -> Self {
+        Self {
+            id: old.id,
+            proposer: old.proposer,
+            description: old.description,
+            votes_for: old.votes_for,
+            votes_against: old.votes_against,
+            executed: old.executed,
+            kind: dao::ProposalKind::Text,
+        }
+    }
+}
+
+// How store::IterableMap lays out each value in storage
+#[derive(BorshSerialize, BorshDeserialize)]
+#[borsh(crate = "near_sdk::borsh")]
+struct MapEntry<V> {
+    value: V,
+    key_index: u32,
+}
+
+// Storage key of slot `index` in the key vector of an IterableMap or IterableSet
+fn key_slot(prefix: &[u8], index: u32) -> Vec<u8> {
+    [prefix, b"v", &index.to_le_bytes()].concat()
+}
+
+// Keys left in the first `len` slots of a key vector that decode as `K`
+fn vector_keys<K: BorshDeserialize>(prefix: Prefix, len: u32) -> Vec<K> {
+    let prefix = prefix.into_storage_key();
+    (0..len)
+        .filter_map(|index| env::storage_read(&key_slot(&prefix, index)).and_then(|raw| K::try_from_slice(&raw).ok()))
+        .collect()
+}
+
+// Keys a u64 keyed IterableMap may hold: every id issued so far plus whatever is
+// left in its key vector, which the maps sharing a prefix overwrite each other in
+fn map_ids(prefix: Prefix, len: u32, ids_issued: u64) -> Vec<u64> {
+    let mut ids: Vec<u64> = vector_keys(prefix, len).into_iter().chain(0..ids_issued).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+// Storage key of the value under `key` in an IterableMap, or of its index in an IterableSet
+fn value_slot(prefix: impl IntoStorageKey, key: &impl BorshSerialize) -> Vec<u8> {
+    let mut raw_key = prefix.into_storage_key();
+    raw_key.push(b'm');
+    raw_key.extend(borsh::to_vec(key).unwrap());
+    env::sha256(&raw_key)
+}
+
+fn decode_map_value<V: BorshDeserialize>(raw: &[u8]) -> Option<V> {
+    MapEntry::<V>::try_from_slice(raw).ok().map(|entry| entry.value)
+}
+
+impl Contract {
+    // Campaigns, users, loan requests, loans and proposals used to share `Prefix::IterableMap`,
+    // and the verified and banned users `Prefix::IterableSet`. Values of the u64 keyed maps
+    // stored under the same id overwrote each other, so each slot holds whatever was written
+    // last and is moved into the map its layout belongs to. Profiles are looked up for every
+    // account the contract knows about.
+    fn rescue_shared_collections(&mut self) {
+        let mut campaigns = IterableMap::new(Prefix::Campaigns);
+        let mut users = IterableMap::new(Prefix::Users);
+        let mut loan_requests = IterableMap::new(Prefix::LoanRequests);
+        let mut loans = IterableMap::new(Prefix::Loans);
+        let mut proposals = IterableMap::new(Prefix::Proposals);
+        let mut verified_users = IterableSet::new(Prefix::VerifiedUsers);
+
+        let map_len = [self.campaigns.len(), self.users.len(), self.loan_requests.len(), self.loans.len(), self.proposals.len()]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+        // Campaign ids were never advanced before versioning, so id 0 is always checked
+        let ids_issued = [self.next_campaign_id.max(1), self.next_loan_request_id, self.next_loan_id, self.proposal_count]
+            .into_iter()
+            .max()
+            .unwrap_or(0);
+
+        // Proposals and loans carry their own id, which tells them apart from a stray value
+        for id in map_ids(Prefix::IterableMap, map_len, ids_issued) {
+            let slot = value_slot(Prefix::IterableMap, &id);
+            let Some(raw) = env::storage_read(&slot) else {
+                continue;
+            };
+            if let Some(campaign) = decode_map_value::<CampaignV0>(&raw) {
+                campaigns.insert(id, Campaign::from(campaign));
+            } else if let Some(loan) = decode_map_value::<LoanV0>(&raw).filter(|loan| loan.loan_id == id) {
+                loans.insert(id, Loan::from(loan));
+            } else if let Some(proposal) = decode_map_value::<ProposalV0>(&raw).filter(|p| p.id == id) {
+                proposals.insert(id, dao::Proposal::from(proposal));
+            } else if let Some(request) = decode_map_value::<LoanRequestV0>(&raw) {
+                loan_requests.insert(id, LoanRequest::from(request));
+            } else {
+                // A profile whose key happens to encode like this id
+                continue;
+            }
+            env::storage_remove(&slot);
+        }
+
+        let set_len = self.verified_users.len().max(self.banned_users.len());
+        let members: Vec<AccountId> = vector_keys(Prefix::IterableSet, set_len);
+
+        let mut accounts: Vec<AccountId> = vector_keys(Prefix::IterableMap, map_len);
+        accounts.extend(members.iter().cloned());
+        accounts.extend(self.trusted_members.iter().cloned());
+        accounts.extend(proposals.values().map(|p| p.proposer.clone()));
+        accounts.extend(loan_requests.values().map(|r| r.borrower.clone()));
+        accounts.extend(loans.values().flat_map(|loan| [loan.borrower.clone(), loan.lender.clone()]));
+        for (id, campaign) in campaigns.iter() {
+            accounts.push(campaign.creator.clone());
+            if let Some(ledger) = self.campaign_ledgers.get(id) {
+                accounts.extend(ledger.keys().cloned());
+            }
+        }
+        accounts.sort_unstable();
+        accounts.dedup();
+
+        for account in accounts {
+            let slot = value_slot(Prefix::IterableMap, &account);
+            // Profiles have kept their layout since version 0
+            if let Some(profile) = env::storage_read(&slot).and_then(|raw| decode_map_value::<UserProfile>(&raw)) {
+                users.insert(account, profile);
+                env::storage_remove(&slot);
+            }
+        }
+
+        // Nothing could ever ban a user, so every member of the shared set is a verified one
+        for account in members {
+            let slot = value_slot(Prefix::IterableSet, &account);
+            if env::storage_remove(&slot) {
+                verified_users.insert(account);
+            }
+        }
+
+        let (map_prefix, set_prefix) = (Prefix::IterableMap.into_storage_key(), Prefix::IterableSet.into_storage_key());
+        for index in 0..map_len {
+            env::storage_remove(&key_slot(&map_prefix, index));
+        }
+        for index in 0..set_len {
+            env::storage_remove(&key_slot(&set_prefix, index));
+        }
+
+        self.campaigns = campaigns;
+        self.users = users;
+        self.loan_requests = loan_requests;
+        self.loans = loans;
+        self.proposals = proposals;
+        self.verified_users = verified_users;
+        self.banned_users = IterableSet::new(Prefix::BannedUsers);
+    }
+}
+
+impl Contract {
+    // Version 0 kept amounts in whole NEAR and every contribution inside its campaign
+    fn from_v0(old: ContractV0) -> Self {
+        // Contributions move out of the campaigns into their ledgers
+        let mut campaign_ledgers: LookupMap<u64, ledger::Ledger> = LookupMap::new(Prefix::CampaignLedgers);
+        for id in map_ids(Prefix::IterableMap, old.campaigns.len(), old.next_campaign_id.max(1)) {
+            let Some(campaign) = env::storage_read(&value_slot(Prefix::IterableMap, &id))
+                .and_then(|raw| decode_map_value::<CampaignV0>(&raw))
+            else {
+                continue;
+            };
+            let ledger = campaign_ledgers.entry(id).or_insert_with(|| IterableMap::new(Prefix::CampaignLedgerOf(id)));
+            for c in campaign.contributions {
+                let amount = c.amount as u128 * ONE_NEAR;
+                ledger
+                    .entry(c.contributor.clone())
+                    .and_modify(|entry| entry.amount.0 += amount)
+                    .or_insert(Contribution { contributor: c.contributor, amount: U128(amount), token: None, tiers: Vec::new(), matches: Vec::new() });
+            }
+        }
+
+        let mut contract = Self {
+            campaigns: old.campaigns,
+            users: old.users,
+            next_campaign_id: old.next_campaign_id,
+            loan_requests: old.loan_requests,
+            loans: old.loans,
+            next_loan_request_id: old.next_loan_request_id,
+            next_loan_id: old.next_loan_id,
+            treasury: NearToken::from_near(old.treasury.into()),
+            proposals: old.proposals,
+            trusted_members: old.trusted_members,
+            proposal_count: old.proposal_count,
+            verified_users: old.verified_users,
+            banned_users: old.banned_users,
+            refund_claims: LookupSet::new(Prefix::RefundClaims),
+            receipts: IterableMap::new(Prefix::Receipts),
+            receipts_per_owner: LookupMap::new(Prefix::ReceiptsPerOwner),
+            next_receipt_id: 0,
+            platform_fee_bps: dao::DEFAULT_PLATFORM_FEE_BPS,
+            proposal_votes: LookupMap::new(Prefix::ProposalBallots),
+            funding_rounds: Vector::new(Prefix::FundingRounds),
+            subscriptions: IterableMap::new(Prefix::Subscriptions),
+            next_subscription_id: 0,
+            campaign_updates: LookupMap::new(Prefix::CampaignUpdates),
+            campaign_codes: LookupMap::new(Prefix::CampaignCodes),
+            campaign_comments: LookupMap::new(Prefix::CampaignComments),
+            campaign_ledgers,
+            policy: dao::Policy::default(),
+            frozen_campaigns: LookupSet::new(Prefix::FrozenCampaigns),
+            withdrawal_votes: LookupSet::new(Prefix::WithdrawalVotes),
+            storage_balances: LookupMap::new(Prefix::StorageBalances),
+            token_treasury: LookupMap::new(Prefix::TokenTreasury),
+            accepted_tokens: LookupSet::new(Prefix::AcceptedTokens),
+            unpaid_payouts: LookupMap::new(Prefix::UnpaidPayouts),
+            round_pledges: LookupMap::new(Prefix::RoundPledges),
+            campaign_rounds: LookupMap::new(Prefix::CampaignRounds),
+            subscriptions_per_creator: LookupMap::new(Prefix::SubscriptionsPerCreator),
+            subscriptions_per_backer: LookupMap::new(Prefix::SubscriptionsPerBacker),
+        };
+        contract.rescue_shared_collections();
+
+        // Keep new campaigns clear of the ones that were migrated
+        let mut campaign_ids: Vec<u64> = contract.campaigns.keys().copied().collect();
+        campaign_ids.sort_unstable();
+        contract.next_campaign_id = campaign_ids.iter().map(|id| id + 1).fold(contract.next_campaign_id, u64::max);
+
+        // Codes were never unique, the oldest campaign keeps a shared code
+        for id in campaign_ids {
+            let code = contract.campaigns[&id].campaign_code.clone();
+            if !code.is_empty() && !contract.campaign_codes.contains_key(&code) {
+                contract.campaign_codes.insert(code, id);
+            }
+        }
+        contract
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Brings the deployed state up to `STATE_VERSION`, one version at a time. Call it
+    // right after deploying new code, it does nothing when the state is already current.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let mut state = VersionedState::read();
+        let contract = loop {
+            state = match state {
+                VersionedState::V0(old) => VersionedState::V1(Box::new(Contract::from_v0(*old))),
+                VersionedState::V1(contract) => break *contract,
+            };
+        };
+
+        write_state_version();
+        contract
+    }
+
+    // Version of the stored state, 0 until `migrate` has run on the first deployment
+    pub fn get_state_version(&self) -> u32 {
+        stored_version().unwrap_or(0)
+    }
+
+}
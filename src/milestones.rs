@@ -0,0 +1,116 @@
+use crate::*;
+
+use near_sdk::env;
+
+// Milestone shares are expressed in basis points of the funds raised
+pub const TOTAL_MILESTONE_SHARE: u16 = 10_000;
+// Milestones are stored inside the campaign, keep it cheap to load
+pub const MAX_MILESTONES: usize = 10;
+
+// A tranche of the campaign funds, held in escrow until backers approve it
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Milestone {
+    pub title: String,
+    pub share: u16, // share of the raised funds in basis points
+    pub due_date: U64,
+    pub proof: Option<String>, // submitted by the creator before release
+    pub released: bool,
+    pub inconclusive_votes: u8, // withdrawal requests for it that missed quorum
+}
+
+// Milestone as declared by the creator in `create_campaign`
+#[near(serializers = [json])]
+pub struct NewMilestone {
+    pub title: String,
+    pub share: u16,
+    pub due_date: U64,
+}
+
+impl Campaign {
+    pub(crate) fn current_milestone(&self) -> Option<usize> {
+        self.milestones.iter().position(|m| !m.released)
+    }
+
+    // The current milestone's due date passed without it being released, so whatever
+    // is left in escrow goes back to the backers
+    pub(crate) fn milestone_overdue(&self) -> bool {
+        self.withdrawal_request.is_none()
+            && self
+                .current_milestone()
+                .is_some_and(|index| env::block_timestamp() > self.milestones[index].due_date.0)
+    }
+
+    pub(crate) fn milestone_amount(&self, index: usize) -> u128 {
+        if index + 1 == self.milestones.len() {
+            // Last tranche takes whatever is left so rounding never locks funds
+            self.total_contributions.0 - self.released.0
+        } else {
+            self.total_contributions.0 * self.milestones[index].share as u128 / TOTAL_MILESTONE_SHARE as u128
+        }
+    }
+}
+
+pub(crate) fn build_milestones(milestones: Vec<NewMilestone>, end_time: U64) -> Vec<Milestone> {
+    require!(milestones.len() <= MAX_MILESTONES, "Too many milestones");
+    let total_share: u32 = milestones.iter().map(|m| m.share as u32).sum();
+    require!(total_share == TOTAL_MILESTONE_SHARE as u32, "Milestone shares must add up to 10000 basis points");
+
+    let mut previous_due_date = end_time.0;
+    milestones
+        .into_iter()
+        .map(|m| {
+            require!(m.share > 0, "Milestone share must be positive");
+            require!(m.due_date.0 > previous_due_date, "Milestone due dates must be after the campaign end and in order");
+            previous_due_date = m.due_date.0;
+
+            Milestone {
+                title: m.title,
+                share: m.share,
+                due_date: m.due_date,
+                proof: None,
+                released: false,
+                inconclusive_votes: 0,
+            }
+        })
+        .collect()
+}
+
+#[near]
+impl Contract {
+
+    pub fn submit_milestone(&mut self, campaign_id: u64, proof: String) {
+        self.require_not_frozen(campaign_id);
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can submit milestones");
+        require!(env::block_timestamp() > campaign.crowdfunding_end_time.into(), "Crowdfunding has not ended yet");
+        require!(campaign.is_funded(), "Campaign has not reached its funding goal");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+        require!(campaign.withdrawal_request.is_none(), "A withdrawal request is already open");
+        campaign.require_payable();
+
+        let index = campaign.current_milestone().expect("No pending milestones");
+        require!(env::block_timestamp() <= campaign.milestones[index].due_date.0, "Milestone due date has passed");
+        campaign.milestones[index].proof = Some(proof);
+
+        // Submitting proof opens a withdrawal request for the backers to vote on
+        let now = env::block_timestamp();
+        campaign.withdrawal_request = Some(voting::WithdrawalRequest {
+            id: campaign.withdrawal_requests,
+            milestone: index as u32,
+            amount: U128(campaign.milestone_amount(index)),
+            created_at: U64(now),
+            voting_ends_at: U64(now + voting::WITHDRAWAL_VOTING_PERIOD),
+            votes_for: U128(0),
+            votes_against: U128(0),
+        });
+        campaign.withdrawal_requests += 1;
+    }
+
+    pub fn get_campaign_milestones(&self, campaign_id: u64) -> Vec<Milestone> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaigns.get(&campaign_id).unwrap().milestones.clone()
+    }
+
+}
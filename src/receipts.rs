@@ -0,0 +1,304 @@
+use crate::*;
+
+use std::collections::HashMap;
+
+use near_sdk::serde_json::{self, json};
+use near_sdk::{env, ext_contract, AccountId, PromiseOrValue};
+
+pub const NFT_METADATA_SPEC: &str = "nft-1.0.0";
+const RECEIPT_NAME: &str = "FusionFund Contribution Receipts";
+const RECEIPT_SYMBOL: &str = "FUSIONRCPT";
+
+// Proof of a single contribution, minted to the contributor as a NEP-171 token
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct ContributionReceipt {
+    pub owner_id: AccountId,
+    pub campaign_id: u64,
+    pub amount: U128,
+    pub token: Option<AccountId>, // currency of the contribution, None for NEAR
+    pub tier: Option<u32>,
+    pub issued_at: U64,
+}
+
+// NEP-177 contract metadata
+#[near(serializers = [json])]
+pub struct NFTContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+// NEP-177 token metadata
+#[near(serializers = [json])]
+pub struct TokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<String>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub starts_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub extra: Option<String>,
+    pub reference: Option<String>,
+    pub reference_hash: Option<String>,
+}
+
+// NEP-171 token as returned by the views
+#[near(serializers = [json])]
+pub struct Token {
+    pub token_id: String,
+    pub owner_id: AccountId,
+    pub metadata: Option<TokenMetadata>,
+    pub approved_account_ids: Option<HashMap<AccountId, u64>>,
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_nft_receiver)]
+pub trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(&mut self, sender_id: AccountId, previous_owner_id: AccountId, token_id: String, msg: String) -> PromiseOrValue<bool>;
+}
+
+fn parse_token_id(token_id: &str) -> u64 {
+    token_id.parse().unwrap_or_else(|_| env::panic_str("Token not found"))
+}
+
+// NEP-297 event for the nep171 standard
+fn log_nft_event(event: &str, data: serde_json::Value) {
+    let event = json!({
+        "standard": "nep171",
+        "version": "1.0.0",
+        "event": event,
+        "data": [data],
+    });
+    env::log_str(&format!("EVENT_JSON:{}", event));
+}
+
+impl Contract {
+    pub(crate) fn mint_receipt(&mut self, campaign_id: u64, owner_id: AccountId, amount: u128, token: Option<AccountId>, tier: Option<u32>) -> u64 {
+        let receipt_id = self.next_receipt_id;
+        self.next_receipt_id += 1;
+
+        self.receipts.insert(receipt_id, ContributionReceipt {
+            owner_id: owner_id.clone(),
+            campaign_id,
+            amount: U128(amount),
+            token,
+            tier,
+            issued_at: U64(env::block_timestamp()),
+        });
+        self.receipts_per_owner.entry(owner_id.clone()).or_default().push(receipt_id);
+
+        log_nft_event("nft_mint", json!({ "owner_id": owner_id, "token_ids": [receipt_id.to_string()] }));
+        receipt_id
+    }
+
+    // Receipts of a withdrawn pledge no longer prove any support
+    pub(crate) fn burn_receipts(&mut self, campaign_id: u64, owner_id: &AccountId) {
+        let Some(owned) = self.receipts_per_owner.get_mut(owner_id) else {
+            return;
+        };
+        let receipts = &mut self.receipts;
+        let mut burned = Vec::new();
+        owned.retain(|id| {
+            let matches = receipts.get(id).is_some_and(|receipt| receipt.campaign_id == campaign_id);
+            if matches {
+                receipts.remove(id);
+                burned.push(id.to_string());
+            }
+            !matches
+        });
+
+        if !burned.is_empty() {
+            log_nft_event("nft_burn", json!({ "owner_id": owner_id, "token_ids": burned }));
+        }
+    }
+
+    fn receipt_token(&self, receipt_id: u64, receipt: &ContributionReceipt) -> Token {
+        // Metadata follows the campaign so receipts don't each store a copy
+        let campaign = self.campaigns.get(&receipt.campaign_id);
+        let media = campaign
+            .and_then(|c| c.images.split(',').map(str::trim).find(|image| !image.is_empty()))
+            .map(str::to_string);
+
+        Token {
+            token_id: receipt_id.to_string(),
+            owner_id: receipt.owner_id.clone(),
+            metadata: Some(TokenMetadata {
+                title: campaign.map(|c| format!("Backer of {}", c.title)),
+                description: Some(format!("Contribution receipt for campaign #{}", receipt.campaign_id)),
+                media,
+                media_hash: None,
+                copies: Some(1),
+                issued_at: Some(receipt.issued_at.0.to_string()),
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: Some(json!({
+                    "campaign_id": receipt.campaign_id,
+                    "amount": receipt.amount,
+                    "token": receipt.token,
+                    "tier": receipt.tier,
+                }).to_string()),
+                reference: None,
+                reference_hash: None,
+            }),
+            approved_account_ids: None,
+        }
+    }
+
+    fn internal_transfer_receipt(&mut self, sender_id: &AccountId, receiver_id: &AccountId, receipt_id: u64, memo: Option<String>) {
+        let receipt = self.receipts.get_mut(&receipt_id).expect("Token not found");
+        require!(&receipt.owner_id == sender_id, "Sender does not own the token");
+        require!(sender_id != receiver_id, "Sender and receiver must differ");
+
+        let campaign = self.campaigns.get(&receipt.campaign_id).expect("Campaign does not exist");
+        require!(campaign.transferable_receipts, "Receipts of this campaign are not transferable");
+
+        receipt.owner_id = receiver_id.clone();
+
+        if let Some(owned) = self.receipts_per_owner.get_mut(sender_id) {
+            owned.retain(|id| *id != receipt_id);
+        }
+        self.receipts_per_owner.entry(receiver_id.clone()).or_default().push(receipt_id);
+
+        log_nft_event("nft_transfer", json!({
+            "old_owner_id": sender_id,
+            "new_owner_id": receiver_id,
+            "token_ids": [receipt_id.to_string()],
+            "memo": memo,
+        }));
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Lets receipts of a campaign change hands, decided before anyone contributes
+    pub fn set_receipts_transferable(&mut self, campaign_id: u64, transferable: bool) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can change receipt settings");
+        require!(!campaign.has_contributions(), "Cannot change receipt settings after contributions");
+
+        campaign.transferable_receipts = transferable;
+    }
+
+    #[payable]
+    pub fn nft_transfer(&mut self, receiver_id: AccountId, token_id: String, approval_id: Option<u64>, memo: Option<String>) {
+        require!(env::attached_deposit() == NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR");
+        require!(approval_id.is_none(), "Approvals are not supported");
+
+        self.internal_transfer_receipt(&env::predecessor_account_id(), &receiver_id, parse_token_id(&token_id), memo);
+    }
+
+    #[payable]
+    pub fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: String,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        require!(env::attached_deposit() == NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR");
+        require!(approval_id.is_none(), "Approvals are not supported");
+
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer_receipt(&sender_id, &receiver_id, parse_token_id(&token_id), memo);
+
+        ext_nft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_NFT_ON_TRANSFER)
+            .nft_on_transfer(sender_id.clone(), sender_id.clone(), token_id.clone(), msg)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_NFT_RESOLVE_TRANSFER)
+                    .nft_resolve_transfer(sender_id, receiver_id, token_id, None),
+            )
+            .into()
+    }
+
+    // Returns true if the token stayed with the receiver
+    #[private]
+    pub fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: String,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        let _ = approved_account_ids;
+        let must_revert = env::promise_result_checked(0, 16)
+            .ok()
+            .and_then(|value| serde_json::from_slice::<bool>(&value).ok())
+            .unwrap_or(true);
+        if !must_revert {
+            return true;
+        }
+
+        let receipt_id = parse_token_id(&token_id);
+        match self.receipts.get(&receipt_id) {
+            Some(receipt) if receipt.owner_id == receiver_id => {
+                self.internal_transfer_receipt(&receiver_id, &previous_owner_id, receipt_id, None);
+                false
+            }
+            // Receiver already passed the token on
+            _ => true,
+        }
+    }
+
+    pub fn nft_token(&self, token_id: String) -> Option<Token> {
+        let receipt_id = token_id.parse().ok()?;
+        self.receipts.get(&receipt_id).map(|receipt| self.receipt_token(receipt_id, receipt))
+    }
+
+    pub fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: RECEIPT_NAME.to_string(),
+            symbol: RECEIPT_SYMBOL.to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    pub fn nft_total_supply(&self) -> U128 {
+        U128(self.receipts.len().into())
+    }
+
+    pub fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.receipts
+            .iter()
+            .skip(from_index.map_or(0, |i| i.0 as usize))
+            .take(limit.map_or(usize::MAX, |l| l as usize))
+            .map(|(id, receipt)| self.receipt_token(*id, receipt))
+            .collect()
+    }
+
+    pub fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        U128(self.receipts_per_owner.get(&account_id).map_or(0, |owned| owned.len() as u128))
+    }
+
+    pub fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.receipts_per_owner
+            .get(&account_id)
+            .map(|owned| {
+                owned
+                    .iter()
+                    .skip(from_index.map_or(0, |i| i.0 as usize))
+                    .take(limit.map_or(usize::MAX, |l| l as usize))
+                    .filter_map(|id| self.receipts.get(id).map(|receipt| self.receipt_token(*id, receipt)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+}
@@ -0,0 +1,68 @@
+use crate::*;
+
+use near_sdk::{env, is_promise_success, AccountId};
+
+impl Campaign {
+    // Refunds are open once backers rejected a withdrawal, the creator cancelled, a
+    // milestone went overdue, or the campaign ended without being funded under its model
+    pub(crate) fn is_refundable(&self) -> bool {
+        self.refunds_open
+            || self.milestone_overdue()
+            || (!self.claimed
+                && env::block_timestamp() > self.crowdfunding_end_time.into()
+                && !self.is_funded())
+    }
+
+    // Pro-rata share of whatever is still held in escrow for `contributed`
+    pub(crate) fn refund_for(&self, contributed: u128) -> u128 {
+        if self.total_contributions.0 == 0 {
+            return 0;
+        }
+        let remaining = self.total_contributions.0 - self.released.0;
+        mul_div(contributed, remaining, self.total_contributions.0)
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn claim_refund(&mut self, campaign_id: u64) -> Promise {
+        let contributor = env::predecessor_account_id();
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+
+        require!(campaign.is_refundable(), "Campaign is not refunding its contributors");
+        require!(!self.refund_claims.contains(&(campaign_id, contributor.clone())), "Refund already claimed");
+
+        let amount = campaign.refund_for(self.stake_of(campaign_id, &contributor));
+        require!(amount > 0, "Nothing to refund");
+
+        self.refund_claims.insert((campaign_id, contributor.clone()));
+
+        Self::transfer_funds(&campaign.token, contributor.clone(), amount)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_REFUND_CALLBACK)
+                    .on_refund_claimed(campaign_id, contributor),
+            )
+    }
+
+    #[private]
+    pub fn on_refund_claimed(&mut self, campaign_id: u64, contributor: AccountId) -> bool {
+        let success = is_promise_success();
+        if !success {
+            // Transfer failed, let the contributor claim again
+            self.refund_claims.remove(&(campaign_id, contributor));
+        }
+        success
+    }
+
+    pub fn get_refundable_balance(&self, campaign_id: u64, account_id: AccountId) -> U128 {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+
+        if !campaign.is_refundable() || self.refund_claims.contains(&(campaign_id, account_id.clone())) {
+            return U128(0);
+        }
+        U128(campaign.refund_for(self.stake_of(campaign_id, &account_id)))
+    }
+
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+use near_sdk::env;
+
+// Keeps campaigns cheap to load on every contribution
+pub const MAX_REWARD_TIERS: usize = 20;
+
+// Perk offered to backers pledging at least `min_pledge`
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct RewardTier {
+    pub title: String,
+    pub description: String,
+    pub min_pledge: U128,
+    pub quantity: Option<u32>, // None for unlimited tiers
+    pub claimed: u32,
+    pub estimated_delivery: U64,
+}
+
+impl Campaign {
+    // Checks a pledge against the tier it selects and counts the claim
+    pub(crate) fn claim_reward_tier(&mut self, tier: u32, amount: u128) {
+        let reward = self.reward_tiers.get_mut(tier as usize).expect("Reward tier does not exist");
+
+        require!(amount >= reward.min_pledge.0, "Pledge is below the reward tier minimum");
+        require!(reward.quantity.is_none_or(|quantity| reward.claimed < quantity), "Reward tier is sold out");
+
+        reward.claimed += 1;
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn add_reward_tier(
+        &mut self,
+        campaign_id: u64,
+        title: String,
+        description: String,
+        min_pledge: U128,
+        quantity: Option<u32>,
+        estimated_delivery: U64,
+    ) -> u32 {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can add reward tiers");
+        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Crowdfunding has ended");
+        require!(campaign.reward_tiers.len() < MAX_REWARD_TIERS, "Too many reward tiers");
+        require!(quantity != Some(0), "Reward tier quantity must be positive");
+
+        campaign.reward_tiers.push(RewardTier {
+            title,
+            description,
+            min_pledge,
+            quantity,
+            claimed: 0,
+            estimated_delivery,
+        });
+
+        (campaign.reward_tiers.len() - 1) as u32
+    }
+
+    pub fn get_reward_tiers(&self, campaign_id: u64) -> Vec<RewardTier> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaigns.get(&campaign_id).unwrap().reward_tiers.clone()
+    }
+
+    // Backers who selected a reward tier, i.e. who claimed what. Pages over all
+    // backers, so a page may hold fewer claims than `limit`.
+    pub fn get_reward_claims(&self, campaign_id: u64, from_index: Option<u32>, limit: Option<u32>) -> Vec<&Contribution> {
+        self.get_campaign_contributions(campaign_id, from_index, limit)
+            .into_iter()
+            .filter(|c| !c.tiers.is_empty())
+            .collect()
+    }
+
+}
@@ -0,0 +1,246 @@
+use crate::*;
+
+use near_sdk::{env, AccountId};
+
+// Bounds the work done when a round is distributed
+pub const MAX_ROUND_CAMPAIGNS: usize = 50;
+
+// Campaign taking part in a round, what it raised while in the round and the match
+// it was allocated
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct RoundCampaign {
+    pub campaign_id: u64,
+    pub roots: U128, // sum of sqrt(pledge) over the backers counted for the round
+    pub total: U128, // what those backers pledged during the round
+    pub matched: U128,
+    pub paid: bool,
+}
+
+// Community grant round whose matching pool comes out of the treasury
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct FundingRound {
+    pub name: String,
+    pub matching_pool: NearToken,
+    pub ends_at: U64,
+    pub campaigns: Vec<RoundCampaign>,
+    pub distributed: bool,
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = 1u128 << ((128 - n.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+impl RoundCampaign {
+    // Quadratic funding weight, (sum of sqrt(pledge))^2 - sum of pledges, counting each
+    // verified backer once with everything they gave during the round
+    fn quadratic_weight(&self) -> u128 {
+        let roots = self.roots.0;
+        roots.checked_mul(roots).expect("Quadratic weight overflow").saturating_sub(self.total.0)
+    }
+
+    // Swaps what a backer counted for in the sums for their new pledge
+    fn requote(&mut self, before: u128, after: u128) {
+        self.roots.0 = self.roots.0 - isqrt(before) + isqrt(after);
+        self.total.0 = self.total.0 - before + after;
+    }
+}
+
+impl Contract {
+    // Backers count while verified and not the creator. Sums are requoted whenever a
+    // ledger entry changes and when a backer is verified or banned, so distributing a
+    // round never has to walk the ledgers
+    pub(crate) fn counts_for_quadratic(&self, campaign_id: u64, backer: &AccountId) -> bool {
+        self.verified_users.contains(backer)
+            && self.campaigns.get(&campaign_id).is_some_and(|campaign| &campaign.creator != backer)
+    }
+
+    // Rounds the campaign joined that are yet to be distributed
+    fn undistributed_rounds(&self, campaign_id: u64) -> Vec<u32> {
+        self.campaign_rounds
+            .get(&campaign_id)
+            .map(|rounds| {
+                rounds
+                    .iter()
+                    .copied()
+                    .filter(|round_id| self.funding_rounds.get(*round_id).is_some_and(|round| !round.distributed))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Rounds that new pledges to the campaign still count towards
+    pub(crate) fn open_rounds(&self, campaign_id: u64) -> Vec<u32> {
+        let now = env::block_timestamp();
+        self.undistributed_rounds(campaign_id)
+            .into_iter()
+            .filter(|round_id| now < self.funding_rounds.get(*round_id).unwrap().ends_at.0)
+            .collect()
+    }
+
+    fn requote_round(&mut self, round_id: u32, campaign_id: u64, before: u128, after: u128) {
+        let round = self.funding_rounds.get_mut(round_id).expect("Funding round does not exist");
+        if let Some(entry) = round.campaigns.iter_mut().find(|c| c.campaign_id == campaign_id) {
+            entry.requote(before, after);
+        }
+    }
+
+    // Adds a new pledge to the backer's share of each round the campaign is in
+    pub(crate) fn add_round_pledges(&mut self, campaign_id: u64, backer: &AccountId, amount: u128) {
+        let counted = self.counts_for_quadratic(campaign_id, backer);
+        for round_id in self.open_rounds(campaign_id) {
+            let pledge = self.round_pledges.entry((round_id, campaign_id, backer.clone())).or_insert(U128(0));
+            let before = pledge.0;
+            pledge.0 += amount;
+            if counted {
+                self.requote_round(round_id, campaign_id, before, before + amount);
+            }
+        }
+    }
+
+    // A backer who takes their contribution out no longer counts, until the round is
+    // distributed
+    pub(crate) fn remove_round_pledges(&mut self, campaign_id: u64, backer: &AccountId) {
+        let counted = self.counts_for_quadratic(campaign_id, backer);
+        for round_id in self.undistributed_rounds(campaign_id) {
+            if let Some(pledge) = self.round_pledges.remove(&(round_id, campaign_id, backer.clone())) {
+                if counted {
+                    self.requote_round(round_id, campaign_id, pledge.0, 0);
+                }
+            }
+        }
+    }
+
+    // Adds the backer's round pledges to the sums when they get verified, or takes
+    // them out when they are banned
+    pub(crate) fn requote_backer_rounds(&mut self, backer: &AccountId, counted: bool) {
+        let campaign_ids = self.users.get(backer).map(|profile| profile.contributions.clone()).unwrap_or_default();
+        for campaign_id in campaign_ids {
+            if self.campaigns.get(&campaign_id).is_none_or(|campaign| &campaign.creator == backer) {
+                continue;
+            }
+            for round_id in self.undistributed_rounds(campaign_id) {
+                if let Some(pledge) = self.round_pledges.get(&(round_id, campaign_id, backer.clone())).map(|p| p.0) {
+                    let (before, after) = if counted { (0, pledge) } else { (pledge, 0) };
+                    self.requote_round(round_id, campaign_id, before, after);
+                }
+            }
+        }
+    }
+
+    // Called when a CreateFundingRound proposal passes
+    pub(crate) fn internal_create_funding_round(&mut self, name: String, matching_pool: NearToken, ends_at: U64) {
+        require!(ends_at.0 > env::block_timestamp(), "Round must end in the future");
+        self.treasury = self.treasury.checked_sub(matching_pool).expect("Treasury cannot cover the matching pool");
+
+        self.funding_rounds.push(FundingRound {
+            name,
+            matching_pool,
+            ends_at,
+            campaigns: Vec::new(),
+            distributed: false,
+        });
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn join_funding_round(&mut self, round_id: u32, campaign_id: u64) {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let round = self.funding_rounds.get_mut(round_id).expect("Funding round does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can join a funding round");
+        require!(campaign.token.is_none(), "Only NEAR campaigns can join a funding round");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+        // Only what the campaign raises from here on counts for the round
+        require!(campaign.is_live(), "Campaign has not launched yet");
+        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Crowdfunding has ended");
+        require!(env::block_timestamp() < round.ends_at.into(), "Funding round has ended");
+        require!(round.campaigns.len() < MAX_ROUND_CAMPAIGNS, "Funding round is full");
+        require!(round.campaigns.iter().all(|c| c.campaign_id != campaign_id), "Campaign already joined this round");
+
+        round.campaigns.push(RoundCampaign { campaign_id, roots: U128(0), total: U128(0), matched: U128(0), paid: false });
+        self.campaign_rounds.entry(campaign_id).or_default().push(round_id);
+    }
+
+    // Splits the matching pool across the round's campaigns by quadratic weight
+    pub fn distribute_funding_round(&mut self, round_id: u32) {
+        let round = self.funding_rounds.get(round_id).expect("Funding round does not exist");
+
+        require!(env::block_timestamp() > round.ends_at.into(), "Funding round has not ended yet");
+        require!(!round.distributed, "Funding round already distributed");
+
+        let weights: Vec<u128> = round.campaigns.iter().map(RoundCampaign::quadratic_weight).collect();
+        let total_weight: u128 = weights.iter().sum();
+        let pool = round.matching_pool.as_yoctonear();
+
+        let round = self.funding_rounds.get_mut(round_id).unwrap();
+        round.distributed = true;
+
+        let mut allocated = 0;
+        if total_weight > 0 {
+            for (entry, weight) in round.campaigns.iter_mut().zip(weights) {
+                entry.matched = U128(mul_div(pool, weight, total_weight));
+                allocated += entry.matched.0;
+            }
+        }
+
+        // Rounding dust, or the whole pool when nobody qualified, goes back
+        self.treasury = self.treasury.saturating_add(NearToken::from_yoctonear(pool - allocated));
+    }
+
+    // Pays a campaign's match to its team once funded, or returns it to the
+    // treasury if the campaign is refunding its backers
+    pub fn claim_round_match(&mut self, round_id: u32, campaign_id: u64) -> Option<Promise> {
+        self.require_not_frozen(campaign_id);
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let round = self.funding_rounds.get_mut(round_id).expect("Funding round does not exist");
+        require!(round.distributed, "Funding round has not been distributed");
+
+        let entry = round
+            .campaigns
+            .iter_mut()
+            .find(|c| c.campaign_id == campaign_id)
+            .expect("Campaign is not part of this round");
+        require!(!entry.paid, "Match already claimed");
+
+        let matched = NearToken::from_yoctonear(entry.matched.0);
+        if matched.is_zero() {
+            entry.paid = true;
+            return None;
+        }
+        if campaign.is_refundable() {
+            entry.paid = true;
+            self.treasury = self.treasury.saturating_add(matched);
+            return None;
+        }
+
+        require!(env::block_timestamp() > campaign.crowdfunding_end_time.into(), "Crowdfunding has not ended yet");
+        require!(campaign.is_funded(), "Campaign has not reached its funding goal");
+        campaign.require_payable();
+
+        entry.paid = true;
+        Some(Self::pay_out(campaign_id, campaign, matched.as_yoctonear()))
+    }
+
+    pub fn get_funding_round(&self, round_id: u32) -> FundingRound {
+        self.funding_rounds.get(round_id).expect("Funding round does not exist").clone()
+    }
+
+    pub fn get_funding_rounds(&self) -> Vec<&FundingRound> {
+        self.funding_rounds.iter().collect()
+    }
+
+}
@@ -0,0 +1,135 @@
+use crate::*;
+
+use near_sdk::{env, AccountId};
+
+pub const MAX_SPONSOR_PLEDGES: usize = 10;
+// Sponsors may match at most 10:1
+pub const MAX_MATCH_RATIO_BPS: u32 = 100_000;
+
+// "I match `ratio_bps` / 10000 of every contribution, up to `cap`"
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct SponsorPledge {
+    pub sponsor: AccountId,
+    pub ratio_bps: u32,
+    pub cap: U128, // locked with the pledge
+    pub matched: U128, // already added to the campaign as the sponsor's contributions
+    pub released: bool, // unused part of the cap went back to the sponsor
+}
+
+// What one sponsor added on top of a backer's contributions
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct SponsorMatch {
+    pub sponsor: AccountId,
+    pub amount: U128,
+}
+
+// Adds `matches` to a backer's, keeping one entry per sponsor
+pub(crate) fn merge_matches(into: &mut Vec<SponsorMatch>, matches: Vec<SponsorMatch>) {
+    for matched in matches {
+        match into.iter_mut().find(|m| m.sponsor == matched.sponsor) {
+            Some(existing) => existing.amount.0 += matched.amount.0,
+            None => into.push(matched),
+        }
+    }
+}
+
+impl Campaign {
+    // Matches a contribution from every pledge with room left and returns what each sponsor
+    // added. Matches are kept on the backer's ledger entry, so they follow that contribution
+    // when it is withdrawn, while the sponsor's share of a refund comes from its pledge.
+    pub(crate) fn apply_sponsor_matches(&mut self, contributor: &AccountId, amount: u128) -> Vec<SponsorMatch> {
+        let mut matches = Vec::new();
+        for pledge in self.sponsor_pledges.iter_mut() {
+            if &pledge.sponsor == contributor {
+                continue;
+            }
+            let matched = mul_div(amount, pledge.ratio_bps.into(), BPS_DENOMINATOR).min(pledge.cap.0 - pledge.matched.0);
+            if matched == 0 {
+                continue;
+            }
+
+            pledge.matched.0 += matched;
+            self.total_contributions.0 += matched;
+            matches.push(SponsorMatch { sponsor: pledge.sponsor.clone(), amount: U128(matched) });
+        }
+        matches
+    }
+
+    // Takes back the matches of a withdrawn contribution, the sponsor gets them back
+    // with the unused part of their pledge
+    pub(crate) fn reverse_sponsor_matches(&mut self, matches: &[SponsorMatch]) {
+        for matched in matches {
+            if let Some(pledge) = self.sponsor_pledges.iter_mut().find(|p| p.sponsor == matched.sponsor) {
+                pledge.matched.0 -= matched.amount.0;
+                self.total_contributions.0 -= matched.amount.0;
+            }
+        }
+    }
+
+    // What `sponsor` matched so far, it is refunded alongside the backers
+    pub(crate) fn matched_by(&self, sponsor: &AccountId) -> u128 {
+        self.sponsor_pledges.iter().find(|p| &p.sponsor == sponsor).map_or(0, |p| p.matched.0)
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Locks the attached deposit as the cap of a matching pledge
+    #[payable]
+    pub fn pledge_match(&mut self, campaign_id: u64, ratio_bps: u32) {
+        let sponsor = env::predecessor_account_id();
+        let cap = env::attached_deposit().as_yoctonear();
+        self.require_not_frozen(campaign_id);
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(campaign.token.is_none(), "Only NEAR campaigns accept matching pledges");
+        require!(sponsor != campaign.creator, "Creators cannot match their own campaign");
+        require!(env::block_timestamp() < campaign.crowdfunding_end_time.into(), "Crowdfunding has ended");
+        require!(!campaign.refunds_open, "Campaign is refunding its contributors");
+        require!(cap > 0, "Attach the amount to pledge");
+        require!(ratio_bps > 0 && ratio_bps <= MAX_MATCH_RATIO_BPS, "Invalid match ratio");
+        require!(campaign.sponsor_pledges.len() < MAX_SPONSOR_PLEDGES, "Too many matching pledges");
+        require!(campaign.sponsor_pledges.iter().all(|p| p.sponsor != sponsor), "Sponsor already pledged to this campaign");
+
+        campaign.sponsor_pledges.push(SponsorPledge {
+            sponsor,
+            ratio_bps,
+            cap: U128(cap),
+            matched: U128(0),
+            released: false,
+        });
+    }
+
+    // Returns the part of the pledge that was never matched once the campaign is over
+    pub fn release_sponsor_pledge(&mut self, campaign_id: u64) -> Promise {
+        let sponsor = env::predecessor_account_id();
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(
+            campaign.refunds_open || env::block_timestamp() > campaign.crowdfunding_end_time.into(),
+            "Crowdfunding has not ended yet"
+        );
+
+        let pledge = campaign
+            .sponsor_pledges
+            .iter_mut()
+            .find(|p| p.sponsor == sponsor)
+            .expect("No matching pledge from this sponsor");
+        require!(!pledge.released, "Pledge already released");
+
+        pledge.released = true;
+        let unused = pledge.cap.0 - pledge.matched.0;
+        require!(unused > 0, "Nothing left to release");
+
+        Promise::new(sponsor).transfer(NearToken::from_yoctonear(unused))
+    }
+
+    pub fn get_sponsor_pledges(&self, campaign_id: u64) -> Vec<SponsorPledge> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaigns.get(&campaign_id).unwrap().sponsor_pledges.clone()
+    }
+
+}
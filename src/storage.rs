@@ -0,0 +1,129 @@
+use crate::*;
+
+use near_sdk::borsh;
+use near_sdk::{env, AccountId};
+
+// Map keys and index slots written next to a receipt or a ledger entry
+const CONTRIBUTION_STORAGE_OVERHEAD: u64 = 64;
+// Key and value of an entry in `storage_balances`
+const STORAGE_BALANCE_ENTRY_BYTES: u64 = 128;
+
+pub(crate) fn storage_cost(bytes: u64) -> NearToken {
+    env::storage_byte_cost().saturating_mul(bytes.into())
+}
+
+// Pays for `bytes` of new storage out of the attached deposit and sends the rest back
+// to `payer`, `what` names the stored item in the error
+pub(crate) fn charge_storage_deposit(payer: AccountId, bytes: u64, what: &str) {
+    charge_storage_deposit_after(payer, NearToken::from_yoctonear(0), bytes, what);
+}
+
+// Same as `charge_storage_deposit` for a deposit that also pays `kept` to the contract
+pub(crate) fn charge_storage_deposit_after(payer: AccountId, kept: NearToken, bytes: u64, what: &str) {
+    let cost = kept.saturating_add(storage_cost(bytes));
+    let deposit = env::attached_deposit();
+    require!(deposit >= cost, format!("Attach at least {} to cover {} storage", cost, what));
+
+    let refund = deposit.saturating_sub(cost);
+    if !refund.is_zero() {
+        Promise::new(payer).transfer(refund).detach();
+    }
+}
+
+impl Contract {
+    // What a contribution adds to storage: its receipt, the receipt id in the owner's
+    // index and, from a new backer, their ledger and funding round entries
+    pub(crate) fn contribution_storage_bytes(&self, campaign_id: u64, contributor: &AccountId, token: &Option<AccountId>, tier: Option<u32>) -> u64 {
+        let receipt = receipts::ContributionReceipt {
+            owner_id: contributor.clone(),
+            campaign_id,
+            amount: U128(0),
+            token: token.clone(),
+            tier,
+            issued_at: U64(0),
+        };
+        let mut bytes = borsh::to_vec(&receipt).unwrap().len() as u64 + CONTRIBUTION_STORAGE_OVERHEAD;
+
+        if self.contribution_of(campaign_id, contributor) == 0 {
+            let entry = Contribution {
+                contributor: contributor.clone(),
+                amount: U128(0),
+                token: token.clone(),
+                tiers: tier.into_iter().collect(),
+                matches: Vec::new(),
+            };
+            bytes += borsh::to_vec(&entry).unwrap().len() as u64 + CONTRIBUTION_STORAGE_OVERHEAD;
+        }
+
+        // A first pledge while the campaign is in a funding round opens the backer's
+        // entry for the round
+        for round_id in self.open_rounds(campaign_id) {
+            let key = (round_id, campaign_id, contributor.clone());
+            if !self.round_pledges.contains_key(&key) {
+                bytes += borsh::to_vec(&(key, U128(0))).unwrap().len() as u64 + CONTRIBUTION_STORAGE_OVERHEAD;
+            }
+        }
+
+        // A sponsor matching the backer for the first time adds to their entry's matches
+        let entry = self.campaign_ledgers.get(&campaign_id).and_then(|ledger| ledger.get(contributor));
+        if let Some(campaign) = self.campaigns.get(&campaign_id) {
+            for pledge in campaign.sponsor_pledges.iter().filter(|p| &p.sponsor != contributor && p.matched.0 < p.cap.0) {
+                if !entry.is_some_and(|c| c.matches.iter().any(|m| m.sponsor == pledge.sponsor)) {
+                    let matched = sponsors::SponsorMatch { sponsor: pledge.sponsor.clone(), amount: U128(0) };
+                    bytes += borsh::to_vec(&matched).unwrap().len() as u64;
+                }
+            }
+        }
+        bytes
+    }
+
+    // Token contributions carry no NEAR, their storage comes out of the balance the
+    // backer deposited with `deposit_storage`
+    pub(crate) fn charge_storage_balance(&mut self, account_id: &AccountId, cost: NearToken) {
+        let balance = self.storage_balances.get_mut(account_id).expect("Deposit NEAR with deposit_storage to cover storage");
+        *balance = balance
+            .checked_sub(cost)
+            .unwrap_or_else(|| env::panic_str(&format!("Storage balance cannot cover {}", cost)));
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Prepays the storage of token contributions, for the caller or `account_id`
+    #[payable]
+    pub fn deposit_storage(&mut self, account_id: Option<AccountId>) -> NearToken {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let mut deposit = env::attached_deposit();
+
+        if !self.storage_balances.contains_key(&account_id) {
+            let entry_cost = storage_cost(STORAGE_BALANCE_ENTRY_BYTES);
+            require!(deposit > entry_cost, format!("Attach more than {} to open a storage balance", entry_cost));
+            deposit = deposit.saturating_sub(entry_cost);
+        }
+
+        let balance = self.storage_balances.entry(account_id).or_insert(NearToken::from_yoctonear(0));
+        *balance = balance.saturating_add(deposit);
+        *balance
+    }
+
+    // Sends back whatever is left of the caller's storage balance
+    pub fn withdraw_storage(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let balance = self.storage_balances.remove(&account_id).expect("No storage balance");
+        require!(!balance.is_zero(), "Storage balance is empty");
+
+        Promise::new(account_id).transfer(balance)
+    }
+
+    pub fn get_storage_balance(&self, account_id: AccountId) -> NearToken {
+        self.storage_balances.get(&account_id).copied().unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    // Attach this on top of a NEAR contribution, or deposit it for a token contribution
+    pub fn get_contribution_storage_cost(&self, campaign_id: u64, account_id: AccountId, tier: Option<u32>) -> NearToken {
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        storage_cost(self.contribution_storage_bytes(campaign_id, &account_id, &campaign.token, tier))
+    }
+
+}
@@ -0,0 +1,163 @@
+use crate::*;
+
+use near_sdk::{borsh, env, AccountId};
+
+// 1 day in nanoseconds
+pub const MIN_SUBSCRIPTION_PERIOD: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_SUBSCRIPTION_PERIODS: u32 = 120;
+const DEFAULT_SUBSCRIPTIONS_PAGE: u32 = 50;
+// Map key and index slots written next to a subscription, or next to an account's index
+const SUBSCRIPTION_STORAGE_OVERHEAD: u64 = 64;
+
+// Prepaid recurring pledge, each period is paid to the creator once it has elapsed
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct Subscription {
+    pub backer: AccountId,
+    pub creator: AccountId,
+    pub amount_per_period: U128,
+    pub period: U64,
+    pub periods: u32, // cut down to the elapsed periods when the backer cancels
+    pub start_time: U64,
+    pub claimed_periods: u32,
+    pub cancelled: bool,
+}
+
+impl Subscription {
+    pub(crate) fn elapsed_periods(&self) -> u32 {
+        let elapsed = env::block_timestamp().saturating_sub(self.start_time.0) / self.period.0;
+        elapsed.min(self.periods.into()) as u32
+    }
+}
+
+impl Contract {
+    // What a subscription adds to storage: the subscription itself, its id in both
+    // accounts' indexes and the indexes of accounts that had none
+    fn subscription_storage_bytes(&self, subscription: &Subscription) -> u64 {
+        let mut bytes = borsh::to_vec(subscription).unwrap().len() as u64 + SUBSCRIPTION_STORAGE_OVERHEAD;
+        if !self.subscriptions_per_creator.contains_key(&subscription.creator) {
+            bytes += borsh::to_vec(&subscription.creator).unwrap().len() as u64 + SUBSCRIPTION_STORAGE_OVERHEAD;
+        }
+        if !self.subscriptions_per_backer.contains_key(&subscription.backer) {
+            bytes += borsh::to_vec(&subscription.backer).unwrap().len() as u64 + SUBSCRIPTION_STORAGE_OVERHEAD;
+        }
+        bytes
+    }
+
+    fn subscription_page(&self, ids: Option<&Vec<u64>>, from_index: Option<u32>, limit: Option<u32>) -> Vec<(u64, &Subscription)> {
+        ids.map(|ids| {
+            ids.iter()
+                .skip(from_index.unwrap_or(0) as usize)
+                .take(limit.unwrap_or(DEFAULT_SUBSCRIPTIONS_PAGE) as usize)
+                .filter_map(|id| self.subscriptions.get(id).map(|subscription| (*id, subscription)))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Prepays `periods` pledges of `amount_per_period` to a creator, the deposit also
+    // pays for the subscription's storage and the rest is sent back
+    #[payable]
+    pub fn subscribe(&mut self, creator: AccountId, amount_per_period: U128, period: U64, periods: u32) -> u64 {
+        let backer = env::predecessor_account_id();
+
+        require!(self.users.contains_key(&creator), "Creator profile not found");
+        require!(backer != creator, "Cannot subscribe to yourself");
+        require!(amount_per_period.0 > 0, "Amount per period must be positive");
+        require!(period.0 >= MIN_SUBSCRIPTION_PERIOD, "Subscription period is too short");
+        require!(periods > 0 && periods <= MAX_SUBSCRIPTION_PERIODS, "Invalid number of periods");
+
+        let total = amount_per_period.0.checked_mul(periods.into()).expect("Subscription total overflow");
+        require!(env::attached_deposit().as_yoctonear() >= total, "Attach the amount for every period");
+
+        let subscription = Subscription {
+            backer: backer.clone(),
+            creator: creator.clone(),
+            amount_per_period,
+            period,
+            periods,
+            start_time: U64(env::block_timestamp()),
+            claimed_periods: 0,
+            cancelled: false,
+        };
+        let bytes = self.subscription_storage_bytes(&subscription);
+        storage::charge_storage_deposit_after(backer.clone(), NearToken::from_yoctonear(total), bytes, "subscription");
+
+        let subscription_id = self.next_subscription_id;
+        self.subscriptions.insert(subscription_id, subscription);
+        self.subscriptions_per_creator.entry(creator).or_default().push(subscription_id);
+        self.subscriptions_per_backer.entry(backer).or_default().push(subscription_id);
+        self.next_subscription_id += 1;
+
+        subscription_id
+    }
+
+    // Pays the creator every elapsed period that hasn't been claimed yet
+    pub fn claim_subscription(&mut self, subscription_id: u64) -> Promise {
+        let subscription = self.subscriptions.get_mut(&subscription_id).expect("Subscription does not exist");
+
+        require!(env::predecessor_account_id() == subscription.creator, "Only the creator can claim the subscription");
+
+        let elapsed = subscription.elapsed_periods();
+        let unclaimed = elapsed - subscription.claimed_periods;
+        require!(unclaimed > 0, "No elapsed periods to claim");
+
+        subscription.claimed_periods = elapsed;
+        let amount = subscription.amount_per_period.0 * u128::from(unclaimed);
+
+        let fee = mul_div(amount, self.platform_fee_bps.into(), BPS_DENOMINATOR);
+        self.treasury = self.treasury.checked_add(NearToken::from_yoctonear(fee)).expect("Treasury overflow");
+
+        Promise::new(subscription.creator.clone()).transfer(NearToken::from_yoctonear(amount - fee))
+    }
+
+    // Refunds the periods that haven't elapsed, elapsed ones stay claimable by the creator
+    pub fn cancel_subscription(&mut self, subscription_id: u64) -> Promise {
+        let subscription = self.subscriptions.get_mut(&subscription_id).expect("Subscription does not exist");
+
+        require!(env::predecessor_account_id() == subscription.backer, "Only the backer can cancel the subscription");
+        require!(!subscription.cancelled, "Subscription already cancelled");
+
+        let elapsed = subscription.elapsed_periods();
+        let remaining = subscription.periods - elapsed;
+        require!(remaining > 0, "Subscription has no periods left");
+
+        subscription.periods = elapsed;
+        subscription.cancelled = true;
+
+        Promise::new(subscription.backer.clone())
+            .transfer(NearToken::from_yoctonear(subscription.amount_per_period.0 * u128::from(remaining)))
+    }
+
+    pub fn get_subscription(&self, subscription_id: u64) -> Subscription {
+        self.subscriptions.get(&subscription_id).expect("Subscription does not exist").clone()
+    }
+
+    pub fn get_creator_subscriptions(&self, creator: AccountId, from_index: Option<u32>, limit: Option<u32>) -> Vec<(u64, &Subscription)> {
+        self.subscription_page(self.subscriptions_per_creator.get(&creator), from_index, limit)
+    }
+
+    pub fn get_backer_subscriptions(&self, backer: AccountId, from_index: Option<u32>, limit: Option<u32>) -> Vec<(u64, &Subscription)> {
+        self.subscription_page(self.subscriptions_per_backer.get(&backer), from_index, limit)
+    }
+
+    // Attach this on top of the subscription total
+    pub fn get_subscription_storage_cost(&self, creator: AccountId, backer: AccountId) -> NearToken {
+        let subscription = Subscription {
+            backer,
+            creator,
+            amount_per_period: U128(0),
+            period: U64(0),
+            periods: 0,
+            start_time: U64(0),
+            claimed_periods: 0,
+            cancelled: false,
+        };
+        storage::storage_cost(self.subscription_storage_bytes(&subscription))
+    }
+
+}
@@ -0,0 +1,158 @@
+use crate::*;
+
+use near_sdk::{env, is_promise_success, AccountId};
+
+pub const MAX_TEAM_MEMBERS: usize = 10;
+
+// Member of a campaign team and their cut of every payout
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct TeamMember {
+    pub account_id: AccountId,
+    pub role: String,
+    pub share_bps: u16,
+    pub accepted: bool, // payouts wait until every member has accepted their role
+}
+
+// Team member as declared by the creator in `set_campaign_team`
+#[near(serializers = [json])]
+pub struct NewTeamMember {
+    pub account_id: AccountId,
+    pub role: String,
+    pub share_bps: u16,
+}
+
+impl Campaign {
+    pub(crate) fn is_team_member(&self, account_id: &AccountId) -> bool {
+        &self.creator == account_id || self.team.iter().any(|m| &m.account_id == account_id)
+    }
+
+    pub(crate) fn team_accepted(&self) -> bool {
+        self.team.iter().all(|m| m.accepted)
+    }
+
+    // Who receives what out of `amount`, the last member takes the rounding remainder
+    pub(crate) fn payout_splits(&self, amount: u128) -> Vec<(AccountId, u128)> {
+        if let Some(beneficiary) = &self.beneficiary {
+            return vec![(beneficiary.clone(), amount)];
+        }
+        if self.team.is_empty() {
+            return vec![(self.creator.clone(), amount)];
+        }
+
+        let mut remaining = amount;
+        let mut splits: Vec<(AccountId, u128)> = self
+            .team
+            .iter()
+            .map(|m| {
+                let share = mul_div(amount, m.share_bps.into(), BPS_DENOMINATOR);
+                remaining -= share;
+                (m.account_id.clone(), share)
+            })
+            .collect();
+        splits.last_mut().unwrap().1 += remaining;
+        splits
+    }
+}
+
+impl Contract {
+    // Pays `amount` of campaign funds out to its team, or to the creator without one.
+    // A share whose transfer fails is kept for its recipient to claim again.
+    pub(crate) fn pay_out(campaign_id: u64, campaign: &Campaign, amount: u128) -> Promise {
+        campaign
+            .payout_splits(amount)
+            .into_iter()
+            .filter(|(_, share)| *share > 0)
+            .map(|(account_id, share)| Self::pay_share(campaign_id, &campaign.token, account_id, share))
+            .reduce(|all, transfer| all.and(transfer))
+            .unwrap_or_else(|| Promise::new(env::current_account_id()))
+    }
+
+    fn pay_share(campaign_id: u64, token: &Option<AccountId>, account_id: AccountId, share: u128) -> Promise {
+        Self::transfer_funds(token, account_id.clone(), share).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_PAYOUT_CALLBACK)
+                .on_payout(campaign_id, account_id, U128(share)),
+        )
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Sets who shares the payouts, fixed once backers start contributing. Members other
+    // than the creator accept their role with `accept_team_role`.
+    pub fn set_campaign_team(&mut self, campaign_id: u64, team: Vec<NewTeamMember>) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+
+        require!(env::predecessor_account_id() == campaign.creator, "Only the creator can set the team");
+        require!(!campaign.has_contributions(), "Cannot change the team after contributions");
+        require!(campaign.beneficiary.is_none(), "Campaigns with a beneficiary pay out to it alone");
+        require!(team.len() <= MAX_TEAM_MEMBERS, "Too many team members");
+
+        if !team.is_empty() {
+            let total: u32 = team.iter().map(|m| u32::from(m.share_bps)).sum();
+            require!(total == BPS_DENOMINATOR as u32, "Team shares must add up to 10000");
+        }
+        for (i, member) in team.iter().enumerate() {
+            require!(!member.role.is_empty(), "Team member role cannot be empty");
+            require!(team[..i].iter().all(|m| m.account_id != member.account_id), "Duplicate team member");
+        }
+
+        let creator = campaign.creator.clone();
+        campaign.team = team
+            .into_iter()
+            .map(|m| TeamMember {
+                accepted: m.account_id == creator,
+                account_id: m.account_id,
+                role: m.role,
+                share_bps: m.share_bps,
+            })
+            .collect();
+    }
+
+    pub fn accept_team_role(&mut self, campaign_id: u64) {
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        let account_id = env::predecessor_account_id();
+
+        let member = campaign
+            .team
+            .iter_mut()
+            .find(|m| m.account_id == account_id)
+            .expect("Only team members can accept a role");
+        require!(!member.accepted, "Team role already accepted");
+
+        member.accepted = true;
+    }
+
+    // A failed transfer, e.g. to an account not registered with the token, leaves the
+    // share owed to its recipient
+    #[private]
+    pub fn on_payout(&mut self, campaign_id: u64, account_id: AccountId, amount: U128) -> bool {
+        let success = is_promise_success();
+        if !success {
+            let owed = self.unpaid_payouts.entry((campaign_id, account_id)).or_insert(U128(0));
+            owed.0 += amount.0;
+        }
+        success
+    }
+
+    // Sends the caller the payout shares that failed to reach them
+    pub fn claim_payout(&mut self, campaign_id: u64) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let owed = self.unpaid_payouts.remove(&(campaign_id, account_id.clone())).expect("No unpaid payout");
+
+        Self::pay_share(campaign_id, &campaign.token, account_id, owed.0)
+    }
+
+    pub fn get_unpaid_payout(&self, campaign_id: u64, account_id: AccountId) -> U128 {
+        self.unpaid_payouts.get(&(campaign_id, account_id)).copied().unwrap_or(U128(0))
+    }
+
+    pub fn get_campaign_team(&self, campaign_id: u64) -> Vec<TeamMember> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaigns.get(&campaign_id).unwrap().team.clone()
+    }
+
+}
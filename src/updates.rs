@@ -0,0 +1,73 @@
+use crate::*;
+
+use near_sdk::borsh;
+use near_sdk::{env, AccountId};
+
+pub const MAX_UPDATE_LENGTH: usize = 2_000;
+pub const MAX_UPDATE_MEDIA: usize = 10;
+const DEFAULT_UPDATES_PAGE: u32 = 20;
+// Storage key and vector slot written next to each update
+const UPDATE_STORAGE_OVERHEAD: u64 = 64;
+
+// News posted to a campaign's backers by its team
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct CampaignUpdate {
+    pub author: AccountId,
+    pub text: String,
+    pub media: Vec<String>, // links to images, videos or IPFS content
+    pub timestamp: U64,
+}
+
+#[near]
+impl Contract {
+
+    // The attached deposit pays for the update's storage, the rest is sent back
+    #[payable]
+    pub fn post_update(&mut self, campaign_id: u64, text: String, media: Option<Vec<String>>) -> u32 {
+        let author = env::predecessor_account_id();
+        let campaign = self.campaigns.get(&campaign_id).expect("Campaign does not exist");
+        let media = media.unwrap_or_default();
+
+        require!(campaign.is_team_member(&author), "Only the campaign team can post updates");
+        require!(!text.is_empty() && text.len() <= MAX_UPDATE_LENGTH, "Invalid update length");
+        require!(media.len() <= MAX_UPDATE_MEDIA, "Too many media references");
+        require!(media.iter().all(|m| !m.is_empty() && m.len() <= MAX_UPDATE_LENGTH), "Invalid media reference");
+
+        let update = CampaignUpdate {
+            author: author.clone(),
+            text,
+            media,
+            timestamp: U64(env::block_timestamp()),
+        };
+        let bytes = borsh::to_vec(&update).unwrap().len() as u64 + UPDATE_STORAGE_OVERHEAD;
+        storage::charge_storage_deposit(author, bytes, "update");
+
+        let updates = self
+            .campaign_updates
+            .entry(campaign_id)
+            .or_insert_with(|| Vector::new(Prefix::CampaignUpdatesOf(campaign_id)));
+        updates.push(update);
+
+        updates.len() - 1
+    }
+
+    // Newest first, `from_index` counts back from the latest update
+    pub fn get_campaign_updates(&self, campaign_id: u64, from_index: Option<u32>, limit: Option<u32>) -> Vec<(u32, &CampaignUpdate)> {
+        let Some(updates) = self.campaign_updates.get(&campaign_id) else {
+            return Vec::new();
+        };
+
+        (0..updates.len())
+            .rev()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(DEFAULT_UPDATES_PAGE) as usize)
+            .filter_map(|index| updates.get(index).map(|update| (index, update)))
+            .collect()
+    }
+
+    pub fn get_campaign_update_count(&self, campaign_id: u64) -> u32 {
+        self.campaign_updates.get(&campaign_id).map_or(0, |updates| updates.len())
+    }
+
+}
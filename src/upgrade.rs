@@ -0,0 +1,59 @@
+use crate::*;
+
+use near_sdk::json_types::Base58CryptoHash;
+use near_sdk::{env, CryptoHash, GasWeight, IntoStorageKey};
+
+// Storage key and bookkeeping written next to staged code
+const STAGED_CODE_STORAGE_OVERHEAD: u64 = 64;
+
+// Staged code is kept in raw storage so it never enters the contract state
+fn staged_code_key(code_hash: &CryptoHash) -> Vec<u8> {
+    [Prefix::StagedCode.into_storage_key().as_slice(), code_hash].concat()
+}
+
+impl Contract {
+    pub(crate) fn require_staged_code(code_hash: &Base58CryptoHash) {
+        require!(env::storage_has_key(&staged_code_key(&code_hash.into())), "Code is not staged");
+    }
+
+    // Called when an UpgradeSelf proposal passes. Deploy and migrate run as one batch on
+    // the contract's own account, so a failing migration leaves the old code in place.
+    pub(crate) fn internal_upgrade_self(&mut self, code_hash: Base58CryptoHash) {
+        let key = staged_code_key(&code_hash.into());
+        let code = env::storage_read(&key).expect("Code is not staged");
+        env::storage_remove(&key);
+
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call_weight("migrate", Vec::new(), NearToken::from_yoctonear(0), Gas::from_tgas(0), GasWeight(1))
+            .detach();
+    }
+}
+
+#[near]
+impl Contract {
+
+    // Takes the new wasm as the raw call input, the attached deposit pays for its storage
+    // and the rest is sent back. Returns the hash to propose the upgrade with.
+    #[payable]
+    pub fn stage_code(&mut self) -> Base58CryptoHash {
+        let member = env::predecessor_account_id();
+        require!(self.is_a_trusted_member(&member), "Only trusted members can stage code");
+
+        let code = env::input().expect("Attach the wasm code as input");
+        require!(!code.is_empty(), "Attach the wasm code as input");
+        let code_hash = env::sha256_array(&code);
+        let key = staged_code_key(&code_hash);
+        require!(!env::storage_has_key(&key), "Code is already staged");
+
+        storage::charge_storage_deposit(member, code.len() as u64 + STAGED_CODE_STORAGE_OVERHEAD, "code");
+
+        env::storage_write(&key, &code);
+        code_hash.into()
+    }
+
+    pub fn is_code_staged(&self, code_hash: Base58CryptoHash) -> bool {
+        env::storage_has_key(&staged_code_key(&code_hash.into()))
+    }
+
+}
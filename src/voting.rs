@@ -0,0 +1,107 @@
+use crate::*;
+
+use near_sdk::env;
+
+// How long backers have to vote on a withdrawal request
+pub const WITHDRAWAL_VOTING_PERIOD: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days in nanoseconds
+// Share of the raised funds (basis points) that has to take part in a vote
+pub const WITHDRAWAL_QUORUM: u16 = 3_000;
+// Votes on a milestone that may miss quorum before its escrow goes back to the backers
+pub const MAX_INCONCLUSIVE_VOTES: u8 = 3;
+
+// Request by the creator to release the next milestone tranche, decided by the backers
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct WithdrawalRequest {
+    pub id: u32, // numbers the campaign's requests, votes are recorded per request
+    pub milestone: u32,
+    pub amount: U128,
+    pub created_at: U64,
+    pub voting_ends_at: U64,
+    pub votes_for: U128, // weighted by contributed amount
+    pub votes_against: U128,
+}
+
+impl WithdrawalRequest {
+    pub(crate) fn quorum_reached(&self, total_contributions: u128) -> bool {
+        (self.votes_for.0 + self.votes_against.0) * 10_000 >= total_contributions * WITHDRAWAL_QUORUM as u128
+    }
+
+    // Outcome is already settled once one side holds a majority of all raised funds
+    pub(crate) fn is_decided(&self, total_contributions: u128) -> bool {
+        self.votes_for.0 * 2 > total_contributions || self.votes_against.0 * 2 >= total_contributions
+    }
+}
+
+#[near]
+impl Contract {
+
+    pub fn vote_on_withdrawal(&mut self, campaign_id: u64, approve: bool) {
+        let backer = env::predecessor_account_id();
+        let weight = self.stake_of(campaign_id, &backer);
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        require!(weight > 0, "Only contributors can vote on withdrawals");
+
+        let request = campaign.withdrawal_request.as_mut().expect("No withdrawal request to vote on");
+        require!(env::block_timestamp() <= request.voting_ends_at.into(), "Voting period has ended");
+        require!(
+            self.withdrawal_votes.insert((campaign_id, request.id, backer)),
+            "Already voted on this withdrawal request"
+        );
+
+        if approve {
+            request.votes_for.0 += weight;
+        } else {
+            request.votes_against.0 += weight;
+        }
+    }
+
+    pub fn finalize_withdrawal_request(&mut self, campaign_id: u64) -> bool {
+        self.require_not_frozen(campaign_id);
+        let campaign = self.campaigns.get_mut(&campaign_id).expect("Campaign does not exist");
+        let request = campaign.withdrawal_request.clone().expect("No withdrawal request to finalize");
+
+        require!(
+            env::block_timestamp() > request.voting_ends_at.into() || request.is_decided(campaign.total_contributions.0),
+            "Voting is still in progress"
+        );
+
+        campaign.withdrawal_request = None;
+
+        if !request.quorum_reached(campaign.total_contributions.0) {
+            // Nothing is decided, the creator may submit the milestone again until it
+            // misses quorum too often
+            let milestone = &mut campaign.milestones[request.milestone as usize];
+            milestone.inconclusive_votes += 1;
+            if milestone.inconclusive_votes >= MAX_INCONCLUSIVE_VOTES {
+                campaign.refunds_open = true;
+            }
+            return false;
+        }
+
+        if request.votes_for.0 > request.votes_against.0 {
+            campaign.milestones[request.milestone as usize].released = true;
+            campaign.released.0 += request.amount.0;
+            if campaign.current_milestone().is_none() {
+                campaign.claimed = true;
+            }
+
+            let fee = campaign.payout_fee(request.amount.0, self.platform_fee_bps);
+            let token = campaign.token.clone();
+
+            Self::pay_out(campaign_id, campaign, request.amount.0 - fee).detach();
+            self.collect_fee(&token, fee);
+            true
+        } else {
+            // Backers rejected the milestone, whatever is left in escrow goes back to them
+            campaign.refunds_open = true;
+            false
+        }
+    }
+
+    pub fn get_withdrawal_request(&self, campaign_id: u64) -> Option<WithdrawalRequest> {
+        require!(self.campaigns.contains_key(&campaign_id), "Campaign does not exist");
+        self.campaigns.get(&campaign_id).unwrap().withdrawal_request.clone()
+    }
+
+}
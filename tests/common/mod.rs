@@ -0,0 +1,134 @@
+// Setup shared by the sandbox tests, not every test file uses every helper
+#![allow(dead_code)]
+
+use near_workspaces::network::Sandbox;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract, Worker};
+use serde_json::{json, Value};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+// 1 day in nanoseconds
+pub const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+// Campaigns in these tests end this long after they are created, 20 seconds in nanoseconds
+pub const CAMPAIGN_DURATION: u64 = 20_000_000_000;
+// Enough blocks to get past CAMPAIGN_DURATION
+pub const BLOCKS_PAST_END: u64 = 200;
+// Most a caller spends on gas in one of these calls
+const GAS_ALLOWANCE: NearToken = NearToken::from_millinear(10);
+
+pub async fn deploy() -> Result<(Worker<Sandbox>, Contract)> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("./").await?;
+
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    contract.call("init").transact().await?.into_result()?;
+    Ok((sandbox, contract))
+}
+
+// New account with a profile, creators and backers need one
+pub async fn user(sandbox: &Worker<Sandbox>, contract: &Contract) -> Result<Account> {
+    let account = sandbox.dev_create_account().await?;
+    account
+        .call(contract.id(), "create_profile")
+        .args_json(json!({"username": account.id(), "bio": null}))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(account)
+}
+
+pub async fn now(sandbox: &Worker<Sandbox>) -> Result<u64> {
+    Ok(sandbox.view_block().await?.timestamp())
+}
+
+// NEAR campaign ending CAMPAIGN_DURATION from now, `extra` adds optional arguments
+// such as milestones
+pub async fn create_campaign(
+    sandbox: &Worker<Sandbox>,
+    contract: &Contract,
+    creator: &Account,
+    code: &str,
+    amount_required: NearToken,
+    extra: Value,
+) -> Result<u64> {
+    let mut args = json!({
+        "end_time": (now(sandbox).await? + CAMPAIGN_DURATION).to_string(),
+        "title": code,
+        "description": "Sandbox campaign",
+        "images": "",
+        "amount_required": amount_required.as_yoctonear().to_string(),
+        "campaign_code": code,
+    });
+    if let (Some(args), Some(extra)) = (args.as_object_mut(), extra.as_object()) {
+        args.extend(extra.clone());
+    }
+
+    let campaign_id = creator
+        .call(contract.id(), "create_campaign")
+        .args_json(args)
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+    Ok(campaign_id)
+}
+
+// Contributes `amount` with the contribution's storage cost attached on top
+pub async fn contribute(contract: &Contract, backer: &Account, campaign_id: u64, amount: NearToken) -> Result<ExecutionFinalResult> {
+    contribute_to_tier(contract, backer, campaign_id, None, amount).await
+}
+
+// Same as `contribute`, selecting reward `tier`
+pub async fn contribute_to_tier(
+    contract: &Contract,
+    backer: &Account,
+    campaign_id: u64,
+    tier: Option<u32>,
+    amount: NearToken,
+) -> Result<ExecutionFinalResult> {
+    let storage: NearToken = contract
+        .view("get_contribution_storage_cost")
+        .args_json(json!({"campaign_id": campaign_id, "account_id": backer.id(), "tier": tier}))
+        .await?
+        .json()?;
+
+    Ok(backer
+        .call(contract.id(), "contribute")
+        .args_json(json!({"campaign_id": campaign_id, "tier": tier}))
+        .deposit(amount.saturating_add(storage))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+pub async fn campaign(contract: &Contract, campaign_id: u64) -> Result<Value> {
+    Ok(contract.view("get_campaign").args_json(json!({"campaign_id": campaign_id})).await?.json()?)
+}
+
+pub async fn balance(account: &Account) -> Result<NearToken> {
+    Ok(account.view_account().await?.balance)
+}
+
+// `account` got `expected` back, less what it paid in gas to ask for it
+pub async fn assert_received(account: &Account, before: NearToken, expected: NearToken) -> Result<()> {
+    let received = balance(account).await?.saturating_sub(before);
+    assert!(
+        received <= expected && received > expected.saturating_sub(GAS_ALLOWANCE),
+        "expected about {expected}, received {received}"
+    );
+    Ok(())
+}
+
+// Makes `member` the first trusted member, only the contract account itself may seat
+// one and only while the DAO has none
+pub async fn join_dao(contract: &Contract, member: &Account) -> Result<()> {
+    contract
+        .call("add_trusted_member")
+        .args_json(json!({"member_id": member.id()}))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
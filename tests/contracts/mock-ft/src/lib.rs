@@ -0,0 +1,86 @@
+// Bare-bones NEP-141 token for the integration tests: no storage registration,
+// anyone can mint.
+use near_sdk::json_types::U128;
+use near_sdk::serde_json;
+use near_sdk::store::LookupMap;
+use near_sdk::{env, ext_contract, near, require, AccountId, Gas, PanicOnDefault, PromiseOrValue};
+
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas::from_tgas(50);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(10);
+
+#[allow(dead_code)]
+#[ext_contract(ext_ft_receiver)]
+trait FungibleTokenReceiver {
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct MockFt {
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl MockFt {
+    #[init]
+    pub fn new() -> Self {
+        Self { balances: LookupMap::new(b"b") }
+    }
+
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        let balance = self.ft_balance_of(account_id.clone()).0;
+        self.balances.insert(account_id, balance + amount.0);
+    }
+
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        let _ = memo;
+        require!(env::attached_deposit().as_yoctonear() == 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+        self.internal_transfer(&env::predecessor_account_id(), &receiver_id, amount.0);
+    }
+
+    #[payable]
+    pub fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String) -> PromiseOrValue<U128> {
+        let _ = memo;
+        require!(env::attached_deposit().as_yoctonear() == 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0);
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_FT_ON_TRANSFER)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+            .into()
+    }
+
+    #[private]
+    pub fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128 {
+        // A failed or malformed receiver call refunds everything
+        let unused = env::promise_result_checked(0, 64)
+            .ok()
+            .and_then(|value| serde_json::from_slice::<U128>(&value).ok())
+            .map_or(amount.0, |unused| unused.0.min(amount.0));
+
+        if unused > 0 {
+            self.internal_transfer(&receiver_id, &sender_id, unused);
+        }
+        U128(amount.0 - unused)
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).copied().unwrap_or(0))
+    }
+
+    fn internal_transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+        let sender_balance = self.ft_balance_of(sender_id.clone()).0;
+        require!(sender_balance >= amount, "Not enough balance");
+        self.balances.insert(sender_id.clone(), sender_balance - amount);
+
+        let receiver_balance = self.ft_balance_of(receiver_id.clone()).0;
+        self.balances.insert(receiver_id.clone(), receiver_balance + amount);
+    }
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+// Hex sha256 of "members only"
+const CODE_HASH: &str = "ee5c79b2ae51cc77f1016a69e1c0026e998fe3b98f16bdf7faf3cf7b355c839f";
+
+async fn contribute_with_code(contract: &Contract, backer: &Account, campaign_id: u64, code: &str) -> Result<ExecutionFinalResult> {
+    let storage: NearToken = contract
+        .view("get_contribution_storage_cost")
+        .args_json(json!({"campaign_id": campaign_id, "account_id": backer.id(), "tier": null}))
+        .await?
+        .json()?;
+
+    Ok(backer
+        .call(contract.id(), "contribute")
+        .args_json(json!({"campaign_id": campaign_id, "access_code": code}))
+        .deposit(NearToken::from_near(1).saturating_add(storage))
+        .max_gas()
+        .transact()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_private_campaign_admits_members_only() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let member = user(&sandbox, &contract).await?;
+    let invitee = user(&sandbox, &contract).await?;
+    let outsider = user(&sandbox, &contract).await?;
+
+    let public_id = create_campaign(&sandbox, &contract, &creator, "PUBLIC", NearToken::from_near(10), json!({})).await?;
+    let private_id = create_campaign(&sandbox, &contract, &creator, "CLUB", NearToken::from_near(10), json!({})).await?;
+    creator
+        .call(contract.id(), "set_campaign_access")
+        .args_json(json!({"campaign_id": private_id, "private": true, "code_hash": CODE_HASH, "allowlist": [member.id()]}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Listings and the code index no longer lead to it
+    let listed: Vec<(u64, Value)> = contract.view("get_all_campaigns").args_json(json!({"from_index": 0, "limit": 10})).await?.json()?;
+    assert_eq!(listed.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![public_id]);
+    let by_code: Option<Value> = contract.view("get_campaign_by_code").args_json(json!({"campaign_code": "CLUB"})).await?.json()?;
+    assert!(by_code.is_none());
+    assert_eq!(campaign(&contract, private_id).await?["campaign_code"], json!(""));
+
+    contribute(&contract, &member, private_id, NearToken::from_near(1)).await?.into_result()?;
+
+    assert!(contribute(&contract, &outsider, private_id, NearToken::from_near(1)).await?.is_failure());
+    assert!(contribute_with_code(&contract, &outsider, private_id, "wrong code").await?.is_failure());
+    contribute_with_code(&contract, &invitee, private_id, "members only").await?.into_result()?;
+
+    let backers: u32 = contract.view("get_campaign_backer_count").args_json(json!({"campaign_id": private_id})).await?.json()?;
+    assert_eq!(backers, 2);
+
+    Ok(())
+}
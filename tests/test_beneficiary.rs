@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_payout_waits_for_the_beneficiary() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let charity = sandbox.dev_create_account().await?;
+
+    let campaign_id = create_campaign(
+        &sandbox,
+        &contract,
+        &creator,
+        "CHARITY",
+        NearToken::from_near(1),
+        json!({"beneficiary": charity.id()}),
+    )
+    .await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    // Backers pledged for the charity, the creator can't redirect the funds
+    let outcome = creator
+        .call(contract.id(), "set_beneficiary")
+        .args_json(json!({"campaign_id": campaign_id, "beneficiary": null}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    let outcome = backer
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    charity
+        .call(contract.id(), "accept_beneficiary")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let creator_before = balance(&creator).await?;
+    let charity_before = balance(&charity).await?;
+    backer
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // 2 NEAR less the 2.5% platform fee, all of it to the charity
+    assert_eq!(balance(&charity).await?.saturating_sub(charity_before), NearToken::from_millinear(1950));
+    assert_eq!(balance(&creator).await?, creator_before);
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::*;
+use near_workspaces::result::ExecutionFinalResult;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+async fn post_comment(contract: &Contract, author: &Account, campaign_id: u64, text: &str, deposit: NearToken) -> Result<ExecutionFinalResult> {
+    Ok(author
+        .call(contract.id(), "post_comment")
+        .args_json(json!({"campaign_id": campaign_id, "text": text}))
+        .deposit(deposit)
+        .transact()
+        .await?)
+}
+
+async fn pin_comment(contract: &Contract, creator: &Account, campaign_id: u64, index: u32) -> Result<ExecutionFinalResult> {
+    Ok(creator
+        .call(contract.id(), "pin_comment")
+        .args_json(json!({"campaign_id": campaign_id, "index": index, "pinned": true}))
+        .transact()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_comment_thread_is_paid_pinned_and_moderated() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let deposit = NearToken::from_millinear(100);
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "THREAD", NearToken::from_near(10), json!({})).await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+
+    // Storage is paid by the author, whatever is left over comes back
+    assert!(post_comment(&contract, &backer, campaign_id, "First!", NearToken::from_yoctonear(0)).await?.is_failure());
+    let before = balance(&backer).await?;
+    post_comment(&contract, &backer, campaign_id, "First!", deposit).await?.into_result()?;
+    assert!(before.saturating_sub(balance(&backer).await?) < NearToken::from_millinear(20));
+
+    for i in 0..5 {
+        post_comment(&contract, &creator, campaign_id, &format!("Update {i}"), deposit).await?.into_result()?;
+    }
+
+    let comments: Vec<(u32, Value)> = contract
+        .view("get_campaign_comments")
+        .args_json(json!({"campaign_id": campaign_id, "from_index": 5}))
+        .await?
+        .json()?;
+    assert_eq!(comments[0].0, 0);
+    assert_eq!(comments[0].1["backer"], json!(true));
+    let comments: Vec<(u32, Value)> =
+        contract.view("get_campaign_comments").args_json(json!({"campaign_id": campaign_id, "limit": 1})).await?.json()?;
+    assert_eq!(comments[0].1["backer"], json!(false));
+
+    for index in 0..5 {
+        pin_comment(&contract, &creator, campaign_id, index).await?.into_result()?;
+    }
+    assert!(pin_comment(&contract, &creator, campaign_id, 5).await?.is_failure());
+
+    // Trusted members hide the backer's comment, which also unpins it
+    join_dao(&contract, contract.as_account()).await?;
+    contract
+        .call("create_proposal")
+        .args_json(json!({"description": "Off topic", "kind": {"HideComment": {"campaign_id": campaign_id, "index": 0}}}))
+        .transact()
+        .await?
+        .into_result()?;
+    contract.call("vote").args_json(json!({"proposal_id": 0, "support": true})).transact().await?.into_result()?;
+    contract.call("execute_proposal").args_json(json!({"proposal_id": 0})).max_gas().transact().await?.into_result()?;
+
+    let pinned: Vec<(u32, Value)> = contract.view("get_pinned_comments").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(pinned.iter().map(|(index, _)| *index).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    let comments: Vec<(u32, Value)> = contract
+        .view("get_campaign_comments")
+        .args_json(json!({"campaign_id": campaign_id, "from_index": 5}))
+        .await?
+        .json()?;
+    assert_eq!(comments[0].1["hidden"], json!(true));
+    assert_eq!(comments[0].1["text"], json!(""));
+    assert!(pin_comment(&contract, &creator, campaign_id, 0).await?.is_failure());
+
+    // The freed slot takes another comment
+    pin_comment(&contract, &creator, campaign_id, 5).await?.into_result()?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_backer_withdraws_pledge_after_extension() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "EXTENDED", NearToken::from_near(10), json!({})).await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    // Pledges are only released while an extension's opt-out window is open
+    let outcome = backer
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    creator
+        .call(contract.id(), "extend_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "additional_time": (4 * DAY).to_string()}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let before = balance(&backer).await?;
+    backer
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&backer, before, NearToken::from_near(2)).await?;
+
+    assert_eq!(campaign(&contract, campaign_id).await?["total_contributions"], json!("0"));
+    let receipts: String = contract.view("nft_supply_for_owner").args_json(json!({"account_id": backer.id()})).await?.json()?;
+    assert_eq!(receipts, "0");
+
+    let outcome = backer
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_campaign_accepts_ft_contributions() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let contract_wasm = near_workspaces::compile_project("./").await?;
+    let ft_wasm = near_workspaces::compile_project("./tests/contracts/mock-ft").await?;
+
+    let contract = sandbox.dev_deploy(&contract_wasm).await?;
+    let ft = sandbox.dev_deploy(&ft_wasm).await?;
+    contract.call("init").transact().await?.into_result()?;
+    ft.call("new").transact().await?.into_result()?;
+
+    // Campaigns only raise in tokens the DAO accepted
+    contract
+        .call("add_trusted_member")
+        .args_json(json!({"member_id": contract.id()}))
+        .transact()
+        .await?
+        .into_result()?;
+    contract
+        .call("create_proposal")
+        .args_json(json!({"description": "List the token", "kind": {"SetTokenAccepted": {"token_id": ft.id(), "accepted": true}}}))
+        .transact()
+        .await?
+        .into_result()?;
+    contract.call("vote").args_json(json!({"proposal_id": 0, "support": true})).transact().await?.into_result()?;
+    contract.call("execute_proposal").args_json(json!({"proposal_id": 0})).transact().await?.into_result()?;
+
+    let creator = sandbox.dev_create_account().await?;
+    let backer = sandbox.dev_create_account().await?;
+    for account in [&creator, &backer] {
+        account
+            .call(contract.id(), "create_profile")
+            .args_json(json!({"username": account.id(), "bio": null}))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    // Campaign funded in the mock token, ending a few seconds from now
+    let now = sandbox.view_block().await?.timestamp();
+    creator
+        .call(contract.id(), "create_campaign")
+        .args_json(json!({
+            "end_time": (now + 5_000_000_000).to_string(),
+            "title": "Token campaign",
+            "description": "Funded in a stablecoin",
+            "images": "",
+            "amount_required": "1000",
+            "campaign_code": "TOKEN",
+            "token": ft.id(),
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+
+    backer
+        .call(ft.id(), "mint")
+        .args_json(json!({"account_id": backer.id(), "amount": "5000"}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Token contributions carry no NEAR, so their storage is prepaid
+    backer
+        .call(contract.id(), "deposit_storage")
+        .args_json(json!({}))
+        .deposit(NearToken::from_millinear(100))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let outcome = backer
+        .call(ft.id(), "ft_transfer_call")
+        .args_json(json!({"receiver_id": contract.id(), "amount": "1500", "msg": "{\"campaign_id\":0}"}))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_success());
+
+    // NEAR contributions are rejected by a token campaign
+    let outcome = backer
+        .call(contract.id(), "contribute")
+        .args_json(json!({"campaign_id": 0}))
+        .deposit(NearToken::from_near(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let campaign: serde_json::Value = contract.view("get_campaign").args_json(json!({"campaign_id": 0})).await?.json()?;
+    assert_eq!(campaign["total_contributions"], "1500");
+
+    let contributions: serde_json::Value =
+        contract.view("get_campaign_contributions").args_json(json!({"campaign_id": 0})).await?.json()?;
+    assert_eq!(contributions[0]["token"], json!(ft.id()));
+
+    sandbox.fast_forward(100).await?;
+
+    creator
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": 0}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The default 2.5% platform fee stays with the contract, in the token
+    let balance: String = ft.view("ft_balance_of").args_json(json!({"account_id": creator.id()})).await?.json()?;
+    assert_eq!(balance, "1463");
+    let fees: String = contract.view("get_token_treasury_balance").args_json(json!({"token_id": ft.id()})).await?.json()?;
+    assert_eq!(fees, "37");
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::json;
+
+async fn withdraw(contract: &Contract, caller: &Account, campaign_id: u64) -> Result<bool> {
+    let outcome = caller
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    Ok(outcome.is_success())
+}
+
+#[tokio::test]
+async fn test_keep_it_all_pays_the_surcharge_below_goal() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(
+        &sandbox,
+        &contract,
+        &creator,
+        "KEEPALL",
+        NearToken::from_near(10),
+        json!({"funding_model": "KeepItAll"}),
+    )
+    .await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    // 2.5% platform fee plus the 5% keep-it-all surcharge
+    let before = balance(&creator).await?;
+    assert!(withdraw(&contract, &backer, campaign_id).await?);
+    let received = balance(&creator).await?.saturating_sub(before);
+    assert_eq!(received, NearToken::from_millinear(1850));
+
+    let treasury: NearToken = contract.view("get_treasury_balance").await?.json()?;
+    assert_eq!(treasury, NearToken::from_millinear(150));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flexible_campaign_pays_out_from_its_minimum_goal() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let flexible = json!({"funding_model": {"Flexible": {"min_goal": NearToken::from_near(3).as_yoctonear().to_string()}}});
+    let short = create_campaign(&sandbox, &contract, &creator, "SHORT", NearToken::from_near(10), flexible.clone()).await?;
+    let enough = create_campaign(&sandbox, &contract, &creator, "ENOUGH", NearToken::from_near(10), flexible).await?;
+
+    contribute(&contract, &backer, short, NearToken::from_near(2)).await?.into_result()?;
+    contribute(&contract, &backer, enough, NearToken::from_near(4)).await?.into_result()?;
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    // Below the minimum the backer gets everything back
+    assert!(!withdraw(&contract, &creator, short).await?);
+    creator
+        .call(contract.id(), "refund_contributors")
+        .args_json(json!({"campaign_id": short}))
+        .transact()
+        .await?
+        .into_result()?;
+    let before = balance(&backer).await?;
+    backer
+        .call(contract.id(), "claim_refund")
+        .args_json(json!({"campaign_id": short}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&backer, before, NearToken::from_near(2)).await?;
+
+    // Past the minimum the creator is paid, without the keep-it-all surcharge
+    let outcome = creator
+        .call(contract.id(), "refund_contributors")
+        .args_json(json!({"campaign_id": enough}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let before = balance(&creator).await?;
+    assert!(withdraw(&contract, &backer, enough).await?);
+    let received = balance(&creator).await?.saturating_sub(before);
+    assert_eq!(received, NearToken::from_millinear(3900));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_round_pays_its_match_to_a_funded_campaign() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backers = [user(&sandbox, &contract).await?, user(&sandbox, &contract).await?];
+    join_dao(&contract, contract.as_account()).await?;
+
+    // The matching pool comes out of the treasury through a proposal
+    creator
+        .call(contract.id(), "contribute_to_treasury")
+        .deposit(NearToken::from_near(3))
+        .transact()
+        .await?
+        .into_result()?;
+    let ends_at = now(&sandbox).await? + CAMPAIGN_DURATION;
+    let pool = NearToken::from_near(1);
+    contract
+        .call("create_proposal")
+        .args_json(json!({
+            "description": "Spring round",
+            "kind": {"CreateFundingRound": {"name": "Spring", "matching_pool": pool, "ends_at": ends_at.to_string()}},
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+    contract.call("vote").args_json(json!({"proposal_id": 0, "support": true})).transact().await?.into_result()?;
+    contract.call("execute_proposal").args_json(json!({"proposal_id": 0})).transact().await?.into_result()?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "ROUND", NearToken::from_near(1), json!({})).await?;
+    creator
+        .call(contract.id(), "join_funding_round")
+        .args_json(json!({"round_id": 0, "campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Only verified backers count towards the match
+    for backer in &backers {
+        contract.call("verify_user").args_json(json!({"user": backer.id()})).transact().await?.into_result()?;
+        contribute(&contract, backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+    }
+
+    let outcome = creator
+        .call(contract.id(), "distribute_funding_round")
+        .args_json(json!({"round_id": 0}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    backers[0]
+        .call(contract.id(), "distribute_funding_round")
+        .args_json(json!({"round_id": 0}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    let round: Value = contract.view("get_funding_round").args_json(json!({"round_id": 0})).await?.json()?;
+    assert_eq!(round["campaigns"][0]["matched"], json!(pool.as_yoctonear().to_string()));
+
+    // The only campaign in the round takes the whole pool
+    let before = balance(&creator).await?;
+    backers[0]
+        .call(contract.id(), "claim_round_match")
+        .args_json(json!({"round_id": 0, "campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_eq!(balance(&creator).await?.saturating_sub(before), pool);
+
+    let outcome = backers[0]
+        .call(contract.id(), "claim_round_match")
+        .args_json(json!({"round_id": 0, "campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let treasury: NearToken = contract.view("get_treasury_balance").await?.json()?;
+    assert_eq!(treasury, NearToken::from_near(2));
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_scheduled_campaign_opens_at_its_start_time() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    // Opens CAMPAIGN_DURATION from now and stays open well past BLOCKS_PAST_END
+    let start = now(&sandbox).await? + CAMPAIGN_DURATION;
+    let campaign_id = create_campaign(
+        &sandbox,
+        &contract,
+        &creator,
+        "SCHEDULED",
+        NearToken::from_near(10),
+        json!({"start_time": start.to_string(), "end_time": (start + 100 * CAMPAIGN_DURATION).to_string()}),
+    )
+    .await?;
+
+    let status: String = contract.view("get_campaign_status").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(status, "Scheduled");
+    let outcome = contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?;
+    assert!(outcome.is_failure(), "contribution accepted before the start time");
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_draft_is_editable_until_published() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "DRAFT", NearToken::from_near(10), json!({"draft": true})).await?;
+
+    let outcome = contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?;
+    assert!(outcome.is_failure(), "draft accepted a contribution");
+
+    creator
+        .call(contract.id(), "edit_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "title": "Final title", "amount_required": NearToken::from_near(5)}))
+        .transact()
+        .await?
+        .into_result()?;
+    creator
+        .call(contract.id(), "publish_campaign")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let outcome = creator
+        .call(contract.id(), "edit_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "title": "Changed again"}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "published campaign was edited");
+
+    let published = campaign(&contract, campaign_id).await?;
+    assert_eq!(published["title"], json!("Final title"));
+    assert_eq!(published["amount_required"], json!(NearToken::from_near(5).as_yoctonear().to_string()));
+
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+
+    Ok(())
+}
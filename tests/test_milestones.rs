@@ -0,0 +1,97 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_milestone_released_after_backers_approve() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let end = now(&sandbox).await? + CAMPAIGN_DURATION;
+    let milestones = json!([
+        {"title": "Prototype", "share": 5000, "due_date": (end + DAY).to_string()},
+        {"title": "Launch", "share": 5000, "due_date": (end + 2 * DAY).to_string()},
+    ]);
+    let campaign_id =
+        create_campaign(&sandbox, &contract, &creator, "MILESTONES", NearToken::from_near(1), json!({"milestones": milestones}))
+            .await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(4)).await?.into_result()?;
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    // Funds stay in escrow, the creator cannot take them in one go
+    let outcome = creator
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    creator
+        .call(contract.id(), "submit_milestone")
+        .args_json(json!({"campaign_id": campaign_id, "proof": "ipfs://prototype"}))
+        .transact()
+        .await?
+        .into_result()?;
+    backer
+        .call(contract.id(), "vote_on_withdrawal")
+        .args_json(json!({"campaign_id": campaign_id, "approve": true}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The backer holds every vote, so the request is decided and anyone may finalize it
+    let before = balance(&creator).await?;
+    let released: bool = backer
+        .call(contract.id(), "finalize_withdrawal_request")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+    assert!(released);
+
+    // Half of the funds, less the 2.5% platform fee
+    let received = balance(&creator).await?.saturating_sub(before);
+    assert_eq!(received, NearToken::from_millinear(1950));
+
+    let campaign = campaign(&contract, campaign_id).await?;
+    assert_eq!(campaign["released"], json!(NearToken::from_near(2).as_yoctonear().to_string()));
+    assert_eq!(campaign["milestones"][0]["released"], json!(true));
+    assert_eq!(campaign["milestones"][1]["released"], json!(false));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_campaign_rejects_too_many_milestones() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+
+    let end = now(&sandbox).await? + CAMPAIGN_DURATION;
+    let milestones: Vec<_> = (1..=11)
+        .map(|i| json!({"title": format!("Step {i}"), "share": if i > 9 { 500 } else { 1000 }, "due_date": (end + i * DAY).to_string()}))
+        .collect();
+    let outcome = creator
+        .call(contract.id(), "create_campaign")
+        .args_json(json!({
+            "end_time": end.to_string(),
+            "title": "Too many steps",
+            "description": "Eleven milestones",
+            "images": "",
+            "amount_required": "1000",
+            "campaign_code": "STEPS",
+            "milestones": milestones,
+        }))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    assert!(format!("{:?}", outcome.into_result().unwrap_err()).contains("Too many milestones"));
+
+    Ok(())
+}
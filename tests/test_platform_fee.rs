@@ -0,0 +1,52 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_withdrawal_pays_the_platform_fee() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "FEE", NearToken::from_near(1), json!({})).await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    // Anyone may trigger the payout, the creator's balance only moves by what it receives
+    let before = balance(&creator).await?;
+    backer
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let received = balance(&creator).await?.saturating_sub(before);
+    assert_eq!(received, NearToken::from_millinear(1950));
+    let treasury: NearToken = contract.view("get_treasury_balance").await?.json()?;
+    assert_eq!(treasury, NearToken::from_millinear(50));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_platform_fee_is_capped() -> Result<()> {
+    let (_sandbox, contract) = deploy().await?;
+    join_dao(&contract, contract.as_account()).await?;
+
+    let outcome = contract
+        .call("create_proposal")
+        .args_json(json!({"description": "Raise the fee", "kind": {"SetPlatformFee": {"fee_bps": 5000}}}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let fee_bps: u16 = contract.view("get_platform_fee_bps").await?.json()?;
+    assert_eq!(fee_bps, 250);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_treasury_transfer_counts_current_members_only() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let alice = sandbox.dev_create_account().await?;
+    let bob = sandbox.dev_create_account().await?;
+    let outsider = sandbox.dev_create_account().await?;
+    let receiver = sandbox.dev_create_account().await?;
+    join_dao(&contract, contract.as_account()).await?;
+    assert!(contract.call("add_trusted_member").args_json(json!({"member_id": alice.id()})).transact().await?.is_failure());
+
+    // Everyone else joins through a proposal the members so far approve
+    let mut members = vec![contract.as_account()];
+    for (proposal_id, member) in [&alice, &bob].into_iter().enumerate() {
+        contract
+            .call("create_proposal")
+            .args_json(json!({"description": "Welcome", "kind": {"AddTrustedMember": {"member_id": member.id()}}}))
+            .transact()
+            .await?
+            .into_result()?;
+        for voter in &members {
+            voter
+                .call(contract.id(), "vote")
+                .args_json(json!({"proposal_id": proposal_id, "support": true}))
+                .transact()
+                .await?
+                .into_result()?;
+        }
+        contract.call("execute_proposal").args_json(json!({"proposal_id": proposal_id})).transact().await?.into_result()?;
+        members.push(member);
+    }
+
+    let self_call = json!({"FunctionCall": {
+        "receiver_id": contract.id(),
+        "method_name": "add_trusted_member",
+        "args": "e30=",
+        "deposit": "0",
+        "gas": "30000000000000",
+    }});
+    let outcome = alice
+        .call(contract.id(), "create_proposal")
+        .args_json(json!({"description": "Let me in", "kind": self_call}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    outsider
+        .call(contract.id(), "contribute_to_treasury")
+        .deposit(NearToken::from_near(3))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let transfer = json!({"TreasuryTransfer": {"receiver_id": receiver.id(), "amount": NearToken::from_near(1)}});
+    let outcome = outsider
+        .call(contract.id(), "create_proposal")
+        .args_json(json!({"description": "Pay me", "kind": transfer}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    alice
+        .call(contract.id(), "create_proposal")
+        .args_json(json!({"description": "Pay the receiver", "kind": transfer}))
+        .transact()
+        .await?
+        .into_result()?;
+    for member in [&alice, &bob] {
+        member.call(contract.id(), "vote").args_json(json!({"proposal_id": 2, "support": true})).transact().await?.into_result()?;
+    }
+
+    // Bob leaves before the transfer executes, Bob's vote no longer counts
+    alice
+        .call(contract.id(), "create_proposal")
+        .args_json(json!({"description": "Remove Bob", "kind": {"RemoveTrustedMember": {"member_id": bob.id()}}}))
+        .transact()
+        .await?
+        .into_result()?;
+    for member in [contract.as_account(), &alice] {
+        member.call(contract.id(), "vote").args_json(json!({"proposal_id": 3, "support": true})).transact().await?.into_result()?;
+    }
+    alice.call(contract.id(), "execute_proposal").args_json(json!({"proposal_id": 3})).transact().await?.into_result()?;
+
+    let outcome = alice.call(contract.id(), "execute_proposal").args_json(json!({"proposal_id": 2})).transact().await?;
+    assert!(outcome.is_failure());
+
+    contract.call("vote").args_json(json!({"proposal_id": 2, "support": true})).transact().await?.into_result()?;
+    let before = balance(&receiver).await?;
+    alice
+        .call(contract.id(), "execute_proposal")
+        .args_json(json!({"proposal_id": 2}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    assert_eq!(balance(&receiver).await?.saturating_sub(before), NearToken::from_near(1));
+    let treasury: NearToken = contract.view("get_treasury_balance").await?.json()?;
+    assert_eq!(treasury, NearToken::from_near(2));
+    let proposal: Value = contract.view("get_proposal").args_json(json!({"proposal_id": 2})).await?.json()?;
+    assert_eq!(proposal["executed"], json!(true));
+    assert_eq!(proposal["votes_for"], json!(2));
+
+    Ok(())
+}
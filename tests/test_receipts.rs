@@ -0,0 +1,108 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, Contract};
+use serde_json::{json, Value};
+
+async fn receipt_id(contract: &Contract, owner: &Account) -> Result<String> {
+    let tokens: Vec<Value> = contract
+        .view("nft_tokens_for_owner")
+        .args_json(json!({"account_id": owner.id()}))
+        .await?
+        .json()?;
+    assert_eq!(tokens.len(), 1);
+    Ok(tokens[0]["token_id"].as_str().unwrap().to_string())
+}
+
+async fn receipt_owner(contract: &Contract, token_id: &str) -> Result<Value> {
+    let token: Value = contract.view("nft_token").args_json(json!({"token_id": token_id})).await?.json()?;
+    Ok(token["owner_id"].clone())
+}
+
+#[tokio::test]
+async fn test_soulbound_receipt_is_minted_and_burned() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let friend = sandbox.dev_create_account().await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "SOULBOUND", NearToken::from_near(10), json!({})).await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    let token_id = receipt_id(&contract, &backer).await?;
+    assert_eq!(receipt_owner(&contract, &token_id).await?, json!(backer.id()));
+
+    let outcome = backer
+        .call(contract.id(), "nft_transfer")
+        .args_json(json!({"receiver_id": friend.id(), "token_id": token_id}))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure(), "soulbound receipt changed hands");
+    assert_eq!(receipt_owner(&contract, &token_id).await?, json!(backer.id()));
+
+    // Pulling the pledge out burns its receipt
+    creator
+        .call(contract.id(), "extend_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "additional_time": (4 * DAY).to_string()}))
+        .transact()
+        .await?
+        .into_result()?;
+    backer
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let token: Option<Value> = contract.view("nft_token").args_json(json!({"token_id": token_id})).await?.json()?;
+    assert!(token.is_none());
+    let supply: String = contract.view("nft_total_supply").await?.json()?;
+    assert_eq!(supply, "0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rejected_transfer_call_returns_receipt() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    // No contract deployed, so nft_on_transfer fails and the transfer is undone
+    let receiver = sandbox.dev_create_account().await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "TRADABLE", NearToken::from_near(10), json!({})).await?;
+    creator
+        .call(contract.id(), "set_receipts_transferable")
+        .args_json(json!({"campaign_id": campaign_id, "transferable": true}))
+        .transact()
+        .await?
+        .into_result()?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+    let token_id = receipt_id(&contract, &backer).await?;
+
+    let kept: bool = backer
+        .call(contract.id(), "nft_transfer_call")
+        .args_json(json!({"receiver_id": receiver.id(), "token_id": token_id, "msg": ""}))
+        .deposit(NearToken::from_yoctonear(1))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+    assert!(!kept);
+    assert_eq!(receipt_owner(&contract, &token_id).await?, json!(backer.id()));
+
+    backer
+        .call(contract.id(), "nft_transfer")
+        .args_json(json!({"receiver_id": receiver.id(), "token_id": token_id}))
+        .deposit(NearToken::from_yoctonear(1))
+        .transact()
+        .await?
+        .into_result()?;
+    assert_eq!(receipt_owner(&contract, &token_id).await?, json!(receiver.id()));
+
+    Ok(())
+}
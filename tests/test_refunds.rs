@@ -0,0 +1,61 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_backers_claim_refunds_once() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let outsider = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "REFUNDS", NearToken::from_near(10), json!({})).await?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    // Nothing to claim while the campaign is running
+    let outcome = backer
+        .call(contract.id(), "claim_refund")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    creator
+        .call(contract.id(), "cancel_campaign")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let refundable: String = contract
+        .view("get_refundable_balance")
+        .args_json(json!({"campaign_id": campaign_id, "account_id": backer.id()}))
+        .await?
+        .json()?;
+    assert_eq!(refundable, NearToken::from_near(2).as_yoctonear().to_string());
+
+    let before = balance(&backer).await?;
+    backer
+        .call(contract.id(), "claim_refund")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&backer, before, NearToken::from_near(2)).await?;
+
+    for account in [&backer, &outsider] {
+        let outcome = account
+            .call(contract.id(), "claim_refund")
+            .args_json(json!({"campaign_id": campaign_id}))
+            .max_gas()
+            .transact()
+            .await?;
+        assert!(outcome.is_failure());
+    }
+
+    Ok(())
+}
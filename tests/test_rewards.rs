@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_reward_tier_limits_and_withdrawn_claims() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let alice = user(&sandbox, &contract).await?;
+    let bob = user(&sandbox, &contract).await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "TIERS", NearToken::from_near(10), json!({})).await?;
+    let tier: u32 = creator
+        .call(contract.id(), "add_reward_tier")
+        .args_json(json!({
+            "campaign_id": campaign_id,
+            "title": "Signed copy",
+            "description": "One of a kind",
+            "min_pledge": NearToken::from_near(2).as_yoctonear().to_string(),
+            "quantity": 1,
+            "estimated_delivery": (now(&sandbox).await? + 30 * DAY).to_string(),
+        }))
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+
+    let outcome = contribute_to_tier(&contract, &alice, campaign_id, Some(tier), NearToken::from_near(1)).await?;
+    assert!(outcome.is_failure(), "pledge below the tier minimum was accepted");
+
+    contribute_to_tier(&contract, &alice, campaign_id, Some(tier), NearToken::from_near(2)).await?.into_result()?;
+
+    let outcome = contribute_to_tier(&contract, &bob, campaign_id, Some(tier), NearToken::from_near(3)).await?;
+    assert!(outcome.is_failure(), "sold out tier was claimed again");
+
+    let tiers: serde_json::Value = contract.view("get_reward_tiers").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(tiers[0]["claimed"], json!(1));
+
+    // Alice opts out of the extension, which frees her reward for someone else
+    creator
+        .call(contract.id(), "extend_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "additional_time": (4 * DAY).to_string()}))
+        .transact()
+        .await?
+        .into_result()?;
+    alice
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    let tiers: serde_json::Value = contract.view("get_reward_tiers").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(tiers[0]["claimed"], json!(0));
+
+    contribute_to_tier(&contract, &bob, campaign_id, Some(tier), NearToken::from_near(3)).await?.into_result()?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_sponsor_match_follows_the_backer() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let sponsor = sandbox.dev_create_account().await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "MATCHED", NearToken::from_near(10), json!({})).await?;
+    sponsor
+        .call(contract.id(), "pledge_match")
+        .args_json(json!({"campaign_id": campaign_id, "ratio_bps": 10000}))
+        .deposit(NearToken::from_near(2))
+        .transact()
+        .await?
+        .into_result()?;
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+
+    let pledges: Value = contract.view("get_sponsor_pledges").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(pledges[0]["matched"], json!(NearToken::from_near(1).as_yoctonear().to_string()));
+    assert_eq!(campaign(&contract, campaign_id).await?["total_contributions"], json!(NearToken::from_near(2).as_yoctonear().to_string()));
+
+    creator
+        .call(contract.id(), "extend_campaign")
+        .args_json(json!({"campaign_id": campaign_id, "additional_time": (4 * DAY).to_string()}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The match belongs to the backer's pledge, the sponsor has nothing of its own to withdraw
+    let outcome = sponsor
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    backer
+        .call(contract.id(), "withdraw_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    let pledges: Value = contract.view("get_sponsor_pledges").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(pledges[0]["matched"], json!("0"));
+    assert_eq!(campaign(&contract, campaign_id).await?["total_contributions"], json!("0"));
+
+    creator
+        .call(contract.id(), "cancel_campaign")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // The whole cap is unused and goes back once
+    let before = balance(&sponsor).await?;
+    sponsor
+        .call(contract.id(), "release_sponsor_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&sponsor, before, NearToken::from_near(2)).await?;
+
+    let outcome = sponsor
+        .call(contract.id(), "release_sponsor_pledge")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    Ok(())
+}
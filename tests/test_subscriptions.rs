@@ -0,0 +1,73 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_cancelled_subscription_refunds_unelapsed_periods() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+
+    // Every period is prepaid, along with the subscription's storage
+    let storage: NearToken = contract
+        .view("get_subscription_storage_cost")
+        .args_json(json!({"creator": creator.id(), "backer": backer.id()}))
+        .await?
+        .json()?;
+    let args = json!({"creator": creator.id(), "amount_per_period": NearToken::from_near(1), "period": DAY.to_string(), "periods": 3});
+    let outcome = backer
+        .call(contract.id(), "subscribe")
+        .args_json(args.clone())
+        .deposit(NearToken::from_near(3))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    let subscription_id: u64 = backer
+        .call(contract.id(), "subscribe")
+        .args_json(args)
+        .deposit(NearToken::from_near(3).saturating_add(storage))
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+
+    // No period has elapsed yet, so the creator has nothing to claim
+    let outcome = creator
+        .call(contract.id(), "claim_subscription")
+        .args_json(json!({"subscription_id": subscription_id}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let before = balance(&backer).await?;
+    backer
+        .call(contract.id(), "cancel_subscription")
+        .args_json(json!({"subscription_id": subscription_id}))
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&backer, before, NearToken::from_near(3)).await?;
+
+    let subscription: Value =
+        contract.view("get_subscription").args_json(json!({"subscription_id": subscription_id})).await?.json()?;
+    assert_eq!(subscription["periods"], json!(0));
+    assert_eq!(subscription["cancelled"], json!(true));
+
+    let subscriptions: Vec<Value> = contract
+        .view("get_backer_subscriptions")
+        .args_json(json!({"backer": backer.id()}))
+        .await?
+        .json()?;
+    assert_eq!(subscriptions.len(), 1);
+
+    let outcome = backer
+        .call(contract.id(), "cancel_subscription")
+        .args_json(json!({"subscription_id": subscription_id}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_withdrawal_is_split_across_the_team() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let backer = user(&sandbox, &contract).await?;
+    let developer = sandbox.dev_create_account().await?;
+
+    let campaign_id = create_campaign(&sandbox, &contract, &creator, "TEAM", NearToken::from_near(1), json!({})).await?;
+
+    let outcome = creator
+        .call(contract.id(), "set_campaign_team")
+        .args_json(json!({"campaign_id": campaign_id, "team": [
+            {"account_id": creator.id(), "role": "lead", "share_bps": 6000},
+            {"account_id": developer.id(), "role": "dev", "share_bps": 3000},
+        ]}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    creator
+        .call(contract.id(), "set_campaign_team")
+        .args_json(json!({"campaign_id": campaign_id, "team": [
+            {"account_id": creator.id(), "role": "lead", "share_bps": 6000},
+            {"account_id": developer.id(), "role": "dev", "share_bps": 4000},
+        ]}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    contribute(&contract, &backer, campaign_id, NearToken::from_near(2)).await?.into_result()?;
+
+    // The team is fixed once backers have contributed
+    let outcome = creator
+        .call(contract.id(), "set_campaign_team")
+        .args_json(json!({"campaign_id": campaign_id, "team": []}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    // Nothing is paid out until every member has accepted their role
+    let outcome = backer
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    developer
+        .call(contract.id(), "accept_team_role")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let creator_before = balance(&creator).await?;
+    let developer_before = balance(&developer).await?;
+    backer
+        .call(contract.id(), "withdraw")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // 1.95 NEAR after the platform fee, split 60/40
+    assert_eq!(balance(&creator).await?.saturating_sub(creator_before), NearToken::from_millinear(1170));
+    assert_eq!(balance(&developer).await?.saturating_sub(developer_before), NearToken::from_millinear(780));
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use near_workspaces::types::NearToken;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_upgrade_keeps_first_deployment_data() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let old_wasm = near_workspaces::compile_project("./tests/contracts/fund-v0").await?;
+    let new_wasm = near_workspaces::compile_project("./").await?;
+
+    let contract = sandbox.dev_deploy(&old_wasm).await?;
+    contract.call("init").transact().await?.into_result()?;
+
+    let creator = sandbox.dev_create_account().await?;
+    let borrower = sandbox.dev_create_account().await?;
+    for account in [contract.as_account(), &creator, &borrower] {
+        account
+            .call(contract.id(), "create_profile")
+            .args_json(json!({"username": account.id(), "bio": null}))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    // Every map shared one prefix, so values with the same id overwrote each other.
+    // Writing proposals, then loan requests, then the campaign leaves proposal 2,
+    // loan request 1 and campaign 0 in storage.
+    contract
+        .call("add_trusted_member")
+        .args_json(json!({"member_id": contract.id()}))
+        .transact()
+        .await?
+        .into_result()?;
+    for description in ["first", "second", "third"] {
+        contract
+            .call("create_proposal")
+            .args_json(json!({"description": description}))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+    for amount in [100, 200] {
+        borrower
+            .call(contract.id(), "create_loan_request")
+            .args_json(json!({"amount": amount, "interest_rate": 5, "duration": "1000"}))
+            .transact()
+            .await?
+            .into_result()?;
+    }
+
+    let now = sandbox.view_block().await?.timestamp();
+    creator
+        .call(contract.id(), "create_campaign")
+        .args_json(json!({
+            "end_time": (now + 3_600_000_000_000).to_string(),
+            "title": "Before the upgrade",
+            "description": "Created on the first deployment",
+            "images": "",
+            "amount_required": 10,
+            "campaign_code": "V0",
+        }))
+        .transact()
+        .await?
+        .into_result()?;
+    // The first deployment only took contributions from the contract account
+    contract
+        .call("contribute")
+        .args_json(json!({"campaign_id": 0}))
+        .deposit(NearToken::from_near(3))
+        .transact()
+        .await?
+        .into_result()?;
+    contract
+        .call("verify_user")
+        .args_json(json!({"user": creator.id()}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Upgrade in place and migrate
+    contract.as_account().deploy(&new_wasm).await?.into_result()?;
+    contract.call("migrate").max_gas().transact().await?.into_result()?;
+
+    let version: u32 = contract.view("get_state_version").await?.json()?;
+    assert_eq!(version, 1);
+
+    let campaign: Value = contract.view("get_campaign").args_json(json!({"campaign_id": 0})).await?.json()?;
+    assert_eq!(campaign["creator"], json!(creator.id()));
+    assert_eq!(campaign["total_contributions"], json!(NearToken::from_near(3).as_yoctonear().to_string()));
+    assert_eq!(campaign["amount_required"], json!(NearToken::from_near(10).as_yoctonear().to_string()));
+
+    let contributions: Value =
+        contract.view("get_campaign_contributions").args_json(json!({"campaign_id": 0})).await?.json()?;
+    assert_eq!(contributions[0]["contributor"], json!(contract.id()));
+
+    let request: Value = contract.view("get_loan_request").args_json(json!({"loan_request_id": 1})).await?.json()?;
+    assert_eq!(request["borrower"], json!(borrower.id()));
+    assert_eq!(request["amount"], json!("200"));
+
+    let proposal: Value = contract.view("get_proposal").args_json(json!({"proposal_id": 2})).await?.json()?;
+    assert_eq!(proposal["description"], json!("third"));
+
+    let profile: Value = contract.view("get_user_profile").args_json(json!({"user_id": creator.id()})).await?.json()?;
+    assert_eq!(profile["created_campaigns"], json!([0]));
+
+    let found: Value = contract.view("get_campaign_by_code").args_json(json!({"campaign_code": "V0"})).await?.json()?;
+    assert_eq!(found[0], json!(0));
+
+    // New campaigns keep clear of the migrated one
+    let campaign_id: u64 = creator
+        .call(contract.id(), "create_campaign")
+        .args_json(json!({
+            "end_time": (now + 3_600_000_000_000).to_string(),
+            "title": "After the upgrade",
+            "description": "Created on the new code",
+            "images": "",
+            "amount_required": "1000",
+            "campaign_code": "V1",
+        }))
+        .transact()
+        .await?
+        .json()?;
+    assert_eq!(campaign_id, 1);
+
+    // Running the migration again leaves the state alone
+    contract.call("migrate").max_gas().transact().await?.into_result()?;
+    let campaign: Value = contract.view("get_campaign").args_json(json!({"campaign_id": 0})).await?.json()?;
+    assert_eq!(campaign["title"], json!("Before the upgrade"));
+
+    Ok(())
+}
+
+// Stages `code`, then proposes, passes and executes an UpgradeSelf proposal with it
+async fn upgrade_through_dao(
+    contract: &near_workspaces::Contract,
+    code: &[u8],
+) -> Result<near_workspaces::result::ExecutionFinalResult, Box<dyn std::error::Error>> {
+    contract
+        .call("add_trusted_member")
+        .args_json(json!({"member_id": contract.id()}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    let code_hash: String = contract
+        .call("stage_code")
+        .args(code.to_vec())
+        .deposit(NearToken::from_near(10))
+        .max_gas()
+        .transact()
+        .await?
+        .json()?;
+    contract
+        .call("create_proposal")
+        .args_json(json!({"description": "Upgrade", "kind": {"UpgradeSelf": {"code_hash": code_hash}}}))
+        .transact()
+        .await?
+        .into_result()?;
+    contract.call("vote").args_json(json!({"proposal_id": 0, "support": true})).transact().await?.into_result()?;
+    let outcome = contract
+        .call("execute_proposal")
+        .args_json(json!({"proposal_id": 0}))
+        .max_gas()
+        .transact()
+        .await?;
+
+    let staged: bool = contract.view("is_code_staged").args_json(json!({"code_hash": code_hash})).await?.json()?;
+    assert!(!staged);
+    Ok(outcome)
+}
+
+#[tokio::test]
+async fn test_dao_upgrades_its_own_code() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("./").await?;
+
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    contract.call("init").transact().await?.into_result()?;
+
+    // Deploy and migrate run in a detached receipt, the call itself succeeds either way
+    let outcome = upgrade_through_dao(&contract, &wasm).await?;
+    assert!(outcome.is_success());
+    assert!(outcome.receipt_failures().is_empty(), "{:?}", outcome.receipt_failures());
+
+    let proposal: Value = contract.view("get_proposal").args_json(json!({"proposal_id": 0})).await?.json()?;
+    assert_eq!(proposal["executed"], json!(true));
+    let version: u32 = contract.view("get_state_version").await?.json()?;
+    assert_eq!(version, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_migration_keeps_the_old_code() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_workspaces::sandbox().await?;
+    let wasm = near_workspaces::compile_project("./").await?;
+    // The first deployment has no migrate method, so the batch fails after deploying it
+    let old_wasm = near_workspaces::compile_project("./tests/contracts/fund-v0").await?;
+
+    let contract = sandbox.dev_deploy(&wasm).await?;
+    contract.call("init").transact().await?.into_result()?;
+
+    let outcome = upgrade_through_dao(&contract, &old_wasm).await?;
+    assert!(outcome.is_success());
+    assert!(!outcome.receipt_failures().is_empty());
+
+    // Still answered by the current code
+    let version: u32 = contract.view("get_state_version").await?.json()?;
+    assert_eq!(version, 1);
+    let proposal: Value = contract.view("get_proposal").args_json(json!({"proposal_id": 0})).await?.json()?;
+    assert_eq!(proposal["executed"], json!(true));
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::*;
+use near_workspaces::types::NearToken;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_rejected_milestone_opens_refunds() -> Result<()> {
+    let (sandbox, contract) = deploy().await?;
+    let creator = user(&sandbox, &contract).await?;
+    let small_backer = user(&sandbox, &contract).await?;
+    let large_backer = user(&sandbox, &contract).await?;
+    let outsider = user(&sandbox, &contract).await?;
+
+    let end = now(&sandbox).await? + CAMPAIGN_DURATION;
+    let milestones = json!([{"title": "Everything", "share": 10000, "due_date": (end + DAY).to_string()}]);
+    let campaign_id =
+        create_campaign(&sandbox, &contract, &creator, "VOTES", NearToken::from_near(1), json!({"milestones": milestones})).await?;
+    contribute(&contract, &small_backer, campaign_id, NearToken::from_near(1)).await?.into_result()?;
+    contribute(&contract, &large_backer, campaign_id, NearToken::from_near(3)).await?.into_result()?;
+
+    sandbox.fast_forward(BLOCKS_PAST_END).await?;
+
+    creator
+        .call(contract.id(), "submit_milestone")
+        .args_json(json!({"campaign_id": campaign_id, "proof": "ipfs://nothing"}))
+        .transact()
+        .await?
+        .into_result()?;
+
+    // Only backers vote, and only once per request
+    let outcome = outsider
+        .call(contract.id(), "vote_on_withdrawal")
+        .args_json(json!({"campaign_id": campaign_id, "approve": true}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+    large_backer
+        .call(contract.id(), "vote_on_withdrawal")
+        .args_json(json!({"campaign_id": campaign_id, "approve": false}))
+        .transact()
+        .await?
+        .into_result()?;
+    let outcome = large_backer
+        .call(contract.id(), "vote_on_withdrawal")
+        .args_json(json!({"campaign_id": campaign_id, "approve": true}))
+        .transact()
+        .await?;
+    assert!(outcome.is_failure());
+
+    let request: serde_json::Value =
+        contract.view("get_withdrawal_request").args_json(json!({"campaign_id": campaign_id})).await?.json()?;
+    assert_eq!(request["votes_against"], json!(NearToken::from_near(3).as_yoctonear().to_string()));
+    assert_eq!(request["votes_for"], json!("0"));
+
+    // Three quarters of the funds voted against, the outcome is settled before the period ends
+    let released: bool = small_backer
+        .call(contract.id(), "finalize_withdrawal_request")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?
+        .json()?;
+    assert!(!released);
+    assert_eq!(campaign(&contract, campaign_id).await?["refunds_open"], json!(true));
+
+    let before = balance(&small_backer).await?;
+    small_backer
+        .call(contract.id(), "claim_refund")
+        .args_json(json!({"campaign_id": campaign_id}))
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+    assert_received(&small_backer, before, NearToken::from_near(1)).await?;
+
+    Ok(())
+}